@@ -4,11 +4,13 @@ const USAGE_MESSAGE: &str = "Usage: cargo run --example generate -- [alphabet si
 const FULL_ALPHABET: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
 fn main() {
-    #[cfg(not(feature = "getrandom"))]
-    panic!("Generate can only be used with the crate feature \"rand\".");
+    #[cfg(not(all(feature = "getrandom", feature = "std")))]
+    panic!("Generate can only be used with the crate features \"getrandom\" and \"std\".");
 
-    #[cfg(feature = "getrandom")]
+    #[cfg(all(feature = "getrandom", feature = "std"))]
     {
+        use std::io::Write;
+
         let mut args = std::env::args();
         args.next();
 
@@ -32,8 +34,11 @@ fn main() {
 
         let mut generator = ShortCodeGenerator::with_alphabet(alphabet, id_length);
 
-        for _ in 0..num_to_generate {
-            println!("{}", generator.next_string());
-        }
+        let stdout = std::io::stdout();
+        let mut out = std::io::BufWriter::new(stdout.lock());
+        generator
+            .write_n(num_to_generate as usize, b'\n', &mut out)
+            .and_then(|_| out.write_all(b"\n"))
+            .expect("Error writing to stdout.");
     }
 }
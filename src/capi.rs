@@ -0,0 +1,209 @@
+//! C-compatible API, enabled by the `capi` crate feature.
+//!
+//! Generators are exposed to C as opaque pointers over a byte alphabet:
+//!
+//! ```c
+//! TinyIdGenerator *gen = tiny_id_generator_new((const uint8_t *)"abc123", 6, 5, NULL);
+//! uint8_t code[16];
+//! size_t len = tiny_id_generator_next(gen, code, sizeof(code));
+//! tiny_id_generator_free(gen);
+//! ```
+//!
+//! Panics are not caught, so they abort the process at the FFI boundary.
+
+use crate::ShortCodeGenerator;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+/// Opaque generator handle used by the C API.
+pub type TinyIdGenerator = ShortCodeGenerator<u8>;
+
+/// Create a generator over the `alphabet_len` bytes at `alphabet`, producing
+/// codes of `length` bytes.
+///
+/// If `seed` is non-null, it must point to 32 bytes used to seed the
+/// generator. Otherwise the generator is seeded from system entropy, which
+/// requires the `getrandom` feature.
+///
+/// Returns null if `alphabet` is null or empty, if the number of possible codes
+/// overflows a `u64`, or if `seed` is null and entropy is unavailable. The result must be released with
+/// [`tiny_id_generator_free`].
+///
+/// # Safety
+///
+/// `alphabet` must point to `alphabet_len` readable bytes, and `seed` must be
+/// null or point to 32 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn tiny_id_generator_new(
+    alphabet: *const u8,
+    alphabet_len: usize,
+    length: usize,
+    seed: *const [u8; 32],
+) -> *mut TinyIdGenerator {
+    if alphabet.is_null() || alphabet_len == 0 {
+        return std::ptr::null_mut();
+    }
+
+    let alphabet = std::slice::from_raw_parts(alphabet, alphabet_len).to_vec();
+
+    let rng = if let Some(seed) = seed.as_ref() {
+        ChaCha12Rng::from_seed(*seed)
+    } else {
+        #[cfg(feature = "getrandom")]
+        {
+            let mut seed: [u8; 32] = Default::default();
+            if getrandom::getrandom(&mut seed).is_err() {
+                return std::ptr::null_mut();
+            }
+            ChaCha12Rng::from_seed(seed)
+        }
+
+        #[cfg(not(feature = "getrandom"))]
+        return std::ptr::null_mut();
+    };
+
+    match ShortCodeGenerator::try_with_alphabet_and_rng(alphabet, length, rng) {
+        Ok(gen) => Box::into_raw(Box::new(gen)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Return the length in bytes of the next code `gen` will produce.
+///
+/// # Safety
+///
+/// `gen` must be a live pointer returned by [`tiny_id_generator_new`].
+#[no_mangle]
+pub unsafe extern "C" fn tiny_id_generator_length(gen: *const TinyIdGenerator) -> usize {
+    let gen = &*gen;
+
+    // If the next code exhausts the current length, it will be one longer.
+    if gen.exhausted()
+        && matches!(
+            gen.effective_strategy(),
+            crate::ExhaustionStrategy::IncreaseLength
+                | crate::ExhaustionStrategy::IncreaseLengthPadded
+                | crate::ExhaustionStrategy::IncreaseLengthDeterministic
+        )
+    {
+        gen.length as usize + 1
+    } else {
+        gen.length as usize
+    }
+}
+
+/// Write the next code from `gen` into `buf`, returning the number of bytes
+/// written.
+///
+/// If `buf_len` is smaller than [`tiny_id_generator_length`], nothing is
+/// written, the generator is not advanced, and 0 is returned.
+///
+/// # Safety
+///
+/// `gen` must be a live pointer returned by [`tiny_id_generator_new`], and
+/// `buf` must point to `buf_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn tiny_id_generator_next(
+    gen: *mut TinyIdGenerator,
+    buf: *mut u8,
+    buf_len: usize,
+) -> usize {
+    if buf.is_null() || buf_len < tiny_id_generator_length(gen) {
+        return 0;
+    }
+
+    let code = (*gen).next_vec();
+    let buf = std::slice::from_raw_parts_mut(buf, buf_len);
+    buf[..code.len()].copy_from_slice(&code);
+    code.len()
+}
+
+/// Release a generator created by [`tiny_id_generator_new`]. Does nothing if
+/// `gen` is null.
+///
+/// # Safety
+///
+/// `gen` must be null or a live pointer returned by [`tiny_id_generator_new`],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tiny_id_generator_free(gen: *mut TinyIdGenerator) {
+    if !gen.is_null() {
+        drop(Box::from_raw(gen));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_capi_round_trip() {
+        let alphabet = b"abc123";
+        let seed = [7u8; 32];
+
+        unsafe {
+            let gen = tiny_id_generator_new(alphabet.as_ptr(), alphabet.len(), 2, &seed);
+            assert!(!gen.is_null());
+
+            let mut seen = HashSet::new();
+            let mut buf = [0u8; 8];
+            for _ in 0..36 {
+                assert_eq!(2, tiny_id_generator_length(gen));
+                let len = tiny_id_generator_next(gen, buf.as_mut_ptr(), buf.len());
+                assert_eq!(2, len);
+                assert!(buf[..len].iter().all(|b| alphabet.contains(b)));
+                seen.insert(buf[..len].to_vec());
+            }
+            assert_eq!(36, seen.len());
+
+            // The space is exhausted, so the next code is longer.
+            assert_eq!(3, tiny_id_generator_length(gen));
+            assert_eq!(0, tiny_id_generator_next(gen, buf.as_mut_ptr(), 2));
+            assert_eq!(3, tiny_id_generator_next(gen, buf.as_mut_ptr(), 3));
+
+            tiny_id_generator_free(gen);
+        }
+    }
+
+    #[test]
+    fn test_capi_seed_is_deterministic() {
+        let alphabet = b"0123456789";
+        let seed = [42u8; 32];
+
+        unsafe {
+            let a = tiny_id_generator_new(alphabet.as_ptr(), alphabet.len(), 6, &seed);
+            let b = tiny_id_generator_new(alphabet.as_ptr(), alphabet.len(), 6, &seed);
+
+            let (mut buf_a, mut buf_b) = ([0u8; 6], [0u8; 6]);
+            for _ in 0..10 {
+                tiny_id_generator_next(a, buf_a.as_mut_ptr(), 6);
+                tiny_id_generator_next(b, buf_b.as_mut_ptr(), 6);
+                assert_eq!(buf_a, buf_b);
+            }
+
+            tiny_id_generator_free(a);
+            tiny_id_generator_free(b);
+        }
+    }
+
+    #[test]
+    fn test_capi_rejects_empty_alphabet() {
+        unsafe {
+            let gen = tiny_id_generator_new(std::ptr::null(), 0, 5, std::ptr::null());
+            assert!(gen.is_null());
+            tiny_id_generator_free(gen);
+        }
+    }
+
+    #[test]
+    fn test_capi_rejects_overflow() {
+        let alphabet = b"0123456789abcdef";
+        let seed = [7u8; 32];
+
+        unsafe {
+            let gen = tiny_id_generator_new(alphabet.as_ptr(), alphabet.len(), 17, &seed);
+            assert!(gen.is_null());
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Check symbols, using the Luhn mod N algorithm. A check symbol detects any
+//! single changed symbol and most transpositions of adjacent symbols.
+
+/// Return the check digit for `digits` in base `base`.
+pub fn check_digit(digits: &[u64], base: u64) -> u64 {
+    let sum = weighted_sum(digits, base, 2);
+    (base - sum % base) % base
+}
+
+/// Returns `true` if the last digit of `digits` is the check digit of the
+/// others.
+pub fn is_valid(digits: &[u64], base: u64) -> bool {
+    weighted_sum(digits, base, 1).is_multiple_of(base)
+}
+
+/// Sum the digits from right to left, alternately multiplying them by
+/// `first_factor` and the other of 1 and 2, and summing the base-`base`
+/// digits of each product.
+fn weighted_sum(digits: &[u64], base: u64, first_factor: u64) -> u64 {
+    let mut factor = first_factor;
+    let mut sum = 0;
+
+    for &digit in digits.iter().rev() {
+        let addend = factor * digit;
+        sum += addend / base + addend % base;
+        factor = 3 - factor;
+    }
+
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luhn() {
+        // The standard base-10 Luhn check digit of 7992739871 is 3.
+        let digits = [7, 9, 9, 2, 7, 3, 9, 8, 7, 1];
+        assert_eq!(3, check_digit(&digits, 10));
+        assert!(is_valid(&[7, 9, 9, 2, 7, 3, 9, 8, 7, 1, 3], 10));
+        assert!(!is_valid(&[7, 9, 9, 2, 7, 3, 9, 8, 7, 1, 4], 10));
+    }
+
+    #[test]
+    fn test_detects_single_errors() {
+        let base = 36;
+        let mut digits = vec![3, 35, 0, 17, 22];
+        digits.push(check_digit(&digits, base));
+
+        for i in 0..digits.len() {
+            for replacement in 0..base {
+                let mut changed = digits.clone();
+                changed[i] = replacement;
+                assert_eq!(replacement == digits[i], is_valid(&changed, base));
+            }
+        }
+    }
+}
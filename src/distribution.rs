@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use rand::distributions::Distribution;
+use rand::Rng;
+
+use crate::render;
+
+/// Samples uniformly random codes of a generator's alphabet and length, for
+/// use with the `rand` crate (e.g. `rng.sample(&distribution)`). Created by
+/// [`crate::ShortCodeGenerator::distribution`].
+///
+/// Unlike [`crate::ShortCodeGenerator`], samples are independent, so the
+/// same code can be sampled more than once: by the birthday problem,
+/// collisions become likely after about the square root of the number of
+/// possible codes. Only use this where repeats are acceptable.
+///
+/// ```
+/// use rand::Rng;
+/// use rand_chacha::rand_core::SeedableRng;
+///
+/// let generator = tiny_id::ShortCodeGenerator::new_lowercase_alphanumeric(6);
+/// let distribution = generator.distribution();
+/// let mut rng = rand_chacha::ChaCha12Rng::from_seed([0; 32]);
+/// let code: String = rng.sample(&distribution);
+/// assert_eq!(6, code.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CodeDistribution<T> {
+    alphabet: Arc<[T]>,
+    length: u32,
+
+    /// The number of possible codes.
+    m: u64,
+}
+
+impl<T> CodeDistribution<T> {
+    pub(crate) fn new(alphabet: Arc<[T]>, length: u32, m: u64) -> Self {
+        CodeDistribution {
+            alphabet,
+            length,
+            m,
+        }
+    }
+}
+
+impl<T: Clone> Distribution<Vec<T>> for CodeDistribution<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<T> {
+        render(rng.gen_range(0..self.m), &self.alphabet, self.length)
+    }
+}
+
+impl Distribution<String> for CodeDistribution<char> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let code: Vec<char> = self.sample(rng);
+        code.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_distribution() {
+        let distribution = CodeDistribution::new(vec!['a', 'b', 'c'].into(), 2, 9);
+        let mut rng = ChaCha12Rng::from_seed([1; 32]);
+
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..9000 {
+            let code: String = rng.sample(&distribution);
+            assert_eq!(2, code.len());
+            *counts.entry(code).or_insert(0) += 1;
+        }
+
+        // Every code is sampled, roughly equally often.
+        assert_eq!(9, counts.len());
+        assert!(counts.values().all(|&count| (800..1200).contains(&count)));
+
+        let code: Vec<char> = distribution.sample(&mut rng);
+        assert!(code.iter().all(|c| "abc".contains(*c)));
+    }
+}
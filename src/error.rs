@@ -0,0 +1,201 @@
+use std::fmt;
+
+/// Reasons an alphabet and length can't be used to build a generator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AlphabetError {
+    /// The alphabet has no symbols.
+    Empty,
+
+    /// The alphabet has only one symbol, so it can only form one code of any
+    /// given length.
+    SingleSymbol,
+
+    /// The number of possible codes (alphabet size to the power of length)
+    /// doesn't fit in a `u64`.
+    Overflow { alphabet_len: usize, length: usize },
+
+    /// The alphabet contains the same symbol more than once, so distinct
+    /// codes could render identically.
+    DuplicateSymbol { symbol: char },
+
+    /// The alphabet contains a whitespace or control character.
+    InvalidSymbol { symbol: char },
+
+    /// No full-period sequence could be constructed for an alphabet of this
+    /// size, so some codes would never be generated.
+    UnsupportedSize { alphabet_len: usize },
+
+    /// The alphabet contains a character that combines with its neighbors
+    /// when rendered, such as a combining accent or a zero-width joiner.
+    CombiningSymbol { symbol: char },
+
+    /// The alphabet can't be changed because the generator has already
+    /// generated codes.
+    CodesIssued,
+
+    /// A replacement alphabet has a different number of symbols than the
+    /// alphabet it replaces.
+    SizeMismatch { expected: usize, actual: usize },
+
+    /// The symbol at `index` of the alphabet also appears earlier in it. Like
+    /// `DuplicateSymbol`, for alphabets of symbols other than `char`.
+    RepeatedSymbol { index: usize },
+
+    /// The alphabet can't be changed because the generator has a setting
+    /// that depends on it.
+    IncompatibleSetting { setting: &'static str },
+}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlphabetError::Empty => write!(f, "Alphabet must not be empty."),
+            AlphabetError::SingleSymbol => {
+                write!(f, "Alphabet must have more than one symbol.")
+            }
+            AlphabetError::Overflow {
+                alphabet_len,
+                length,
+            } => write!(
+                f,
+                "Number of codes with an alphabet of {} symbols and length {} overflows a u64.",
+                alphabet_len, length
+            ),
+            AlphabetError::DuplicateSymbol { symbol } => {
+                write!(f, "Alphabet contains {:?} more than once.", symbol)
+            }
+            AlphabetError::InvalidSymbol { symbol } => write!(
+                f,
+                "Alphabet contains the whitespace or control character {:?}.",
+                symbol
+            ),
+            AlphabetError::CombiningSymbol { symbol } => write!(
+                f,
+                "Alphabet contains {:?} (U+{:04X}), which combines with neighboring characters.",
+                symbol, *symbol as u32
+            ),
+            AlphabetError::UnsupportedSize { alphabet_len } => write!(
+                f,
+                "Alphabets of {} symbols are not supported.",
+                alphabet_len
+            ),
+            AlphabetError::CodesIssued => write!(
+                f,
+                "Alphabet can't be changed after codes have been generated."
+            ),
+            AlphabetError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Replacement alphabet has {} symbols, but must have {}.",
+                actual, expected
+            ),
+            AlphabetError::RepeatedSymbol { index } => write!(
+                f,
+                "Alphabet symbol at index {} also appears earlier in the alphabet.",
+                index
+            ),
+            AlphabetError::IncompatibleSetting { setting } => write!(
+                f,
+                "Alphabet can't be changed on a generator with {}.",
+                setting
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
+/// Reasons a cursor token can't be applied to a generator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TokenError {
+    /// The token isn't a token produced by
+    /// [`crate::ShortCodeGenerator::cursor_token`].
+    Malformed,
+
+    /// The token was produced by a generator with a different configuration
+    /// (alphabet, length, rng or partitioning).
+    Mismatch,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::Malformed => write!(f, "Cursor token is malformed."),
+            TokenError::Mismatch => {
+                write!(f, "Cursor token was produced by a different generator.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Reasons a generator can't be split into partitions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParallelError {
+    /// The generator is already a partition.
+    AlreadyParallel,
+
+    /// The number of partitions is zero.
+    ZeroPartitions,
+
+    /// The partition index is not less than the number of partitions.
+    IndexOutOfRange { index: u32, total: u32 },
+
+    /// There are more partitions than codes, so some partitions could never
+    /// generate a code.
+    ExceedsCapacity { partitions: u32, capacity: u64 },
+
+    /// The position is past the end of the code space.
+    PositionOutOfRange { position: u64, capacity: u64 },
+
+    /// A block of zero codes was requested.
+    EmptyBlock,
+
+    /// More codes were requested than the generator has left.
+    InsufficientCapacity { requested: u64, remaining: u64 },
+}
+
+impl fmt::Display for ParallelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParallelError::AlreadyParallel => {
+                write!(f, "Generator is already partitioned.")
+            }
+            ParallelError::ZeroPartitions => {
+                write!(f, "Number of partitions must not be zero.")
+            }
+            ParallelError::IndexOutOfRange { index, total } => write!(
+                f,
+                "Partition index {} is out of range for {} partitions.",
+                index, total
+            ),
+            ParallelError::ExceedsCapacity {
+                partitions,
+                capacity,
+            } => write!(
+                f,
+                "Can't split a space of {} codes into {} partitions.",
+                capacity, partitions
+            ),
+            ParallelError::PositionOutOfRange { position, capacity } => write!(
+                f,
+                "Position {} is past the end of the code space ({}).",
+                position, capacity
+            ),
+            ParallelError::EmptyBlock => write!(f, "Can't reserve a block of zero codes."),
+            ParallelError::InsufficientCapacity {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "Can't reserve {} codes when {} remain.",
+                requested, remaining
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParallelError {}
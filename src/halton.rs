@@ -0,0 +1,216 @@
+use crate::{checked_capacity, render, AlphabetError, ExhaustionStrategy};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Generates fixed-length codes whose values are spread evenly over the code
+/// space, using the base-N van der Corput sequence (the one-dimensional Halton
+/// sequence).
+///
+/// The `k`-th code is `k` with its base-N digits reversed. Any run of
+/// consecutive codes is spread out as evenly as possible: for example, the
+/// first `N` codes all start with a different symbol, and the first `N^2`
+/// codes all have different two-symbol prefixes. Compared to
+/// [`crate::ShortCodeGenerator`], which visits the space in a pseudorandom
+/// order, consecutive codes here never cluster, but the sequence is entirely
+/// predictable.
+///
+/// Every code is generated exactly once per cycle.
+///
+/// ```
+/// let mut generator = tiny_id::HaltonGenerator::with_alphabet(
+///     "0123456789".chars().collect(),
+///     3,
+/// );
+/// assert_eq!("000", generator.next_string());
+/// assert_eq!("100", generator.next_string());
+/// assert_eq!("200", generator.next_string());
+/// ```
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct HaltonGenerator<T: Clone> {
+    alphabet: Vec<T>,
+    length: u32,
+
+    /// The index in the sequence of the next code to be generated.
+    next: u64,
+
+    exhaustion_strategy: ExhaustionStrategy,
+}
+
+/// Reverse the lowest `length` base-`base` digits of `index`. This is the
+/// `index`-th value of the van der Corput sequence, scaled up to an integer,
+/// and is its own inverse.
+pub(crate) fn reverse_digits(mut index: u64, base: u64, length: u32) -> u64 {
+    let mut value = 0;
+    for _ in 0..length {
+        value = value * base + index % base;
+        index /= base;
+    }
+    value
+}
+
+impl HaltonGenerator<char> {
+    /// Return the next code, represented as a string.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_string(&mut self) -> String {
+        self.next_vec().into_iter().collect()
+    }
+}
+
+impl<T: Clone> HaltonGenerator<T> {
+    /// Create a generator using a given alphabet, starting from the code made
+    /// up entirely of the first symbol.
+    ///
+    /// Panics if the alphabet is empty or the number of possible codes
+    /// overflows a `u64`. See [`HaltonGenerator::try_with_alphabet`] for a
+    /// non-panicking version.
+    pub fn with_alphabet(alphabet: Vec<T>, length: usize) -> Self {
+        Self::try_with_alphabet(alphabet, length).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Create a generator using a given alphabet, starting from the code made
+    /// up entirely of the first symbol.
+    ///
+    /// Returns an error if the alphabet is empty or the number of possible
+    /// codes overflows a `u64`.
+    pub fn try_with_alphabet(alphabet: Vec<T>, length: usize) -> Result<Self, AlphabetError> {
+        checked_capacity(alphabet.len(), length)?;
+
+        Ok(Self {
+            alphabet,
+            length: length as u32,
+            next: 0,
+            exhaustion_strategy: ExhaustionStrategy::Panic,
+        })
+    }
+
+    /// Set the exhaustion strategy of this generator. Preserves other state.
+    ///
+    /// The default is [`ExhaustionStrategy::Panic`].
+    /// [`ExhaustionStrategy::IncreaseLengthDeterministic`] behaves like
+    /// `IncreaseLength`, since no randomness is involved.
+    /// [`ExhaustionStrategy::IncreaseLengthPadded`] is not supported and
+    /// behaves like `Panic`.
+    pub fn exhaustion_strategy(mut self, strategy: ExhaustionStrategy) -> Self {
+        self.exhaustion_strategy = strategy;
+        self
+    }
+
+    /// Return the next code, represented as an integer.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_int(&mut self) -> u64 {
+        let alphabet_size = self.alphabet.len() as u64;
+
+        let m = checked_capacity(self.alphabet.len(), self.length as usize)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        if self.next >= m {
+            match self.exhaustion_strategy {
+                ExhaustionStrategy::Cycle => {}
+                ExhaustionStrategy::IncreaseLength
+                | ExhaustionStrategy::IncreaseLengthDeterministic => {
+                    checked_capacity(self.alphabet.len(), self.length as usize + 1)
+                        .unwrap_or_else(|err| panic!("Can't increase code length: {}", err));
+                    self.length += 1;
+                }
+                _ => panic!(
+                    "HaltonGenerator exhausted: base={} length={} capacity={}",
+                    alphabet_size, self.length, m
+                ),
+            }
+            self.next = 0;
+        }
+
+        let index = self.next;
+        self.next += 1;
+
+        reverse_digits(index, alphabet_size, self.length)
+    }
+
+    /// Return the next code, represented as a vector.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_vec(&mut self) -> Vec<T> {
+        let value = self.next_int();
+        let mut result = render(value, &self.alphabet, self.length);
+
+        // Put the most significant symbol first, so that the leading symbols
+        // are the ones that are spread out.
+        result.reverse();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_full_coverage() {
+        for (alphabet_size, length) in [(2, 8), (10, 3), (7, 4)] {
+            let alphabet: Vec<u32> = (0..alphabet_size).collect();
+            let mut gen = HaltonGenerator::with_alphabet(alphabet, length);
+            let capacity = (alphabet_size as u64).pow(length as u32);
+
+            let seen: HashSet<Vec<u32>> = (0..capacity).map(|_| gen.next_vec()).collect();
+            assert_eq!(capacity, seen.len() as u64);
+        }
+    }
+
+    #[test]
+    fn test_spread() {
+        let mut gen = HaltonGenerator::with_alphabet("0123456789".chars().collect(), 4);
+
+        // Each block of 100 consecutive codes covers every two-symbol prefix.
+        for _ in 0..100 {
+            let prefixes: HashSet<String> = (0..100)
+                .map(|_| gen.next_string()[..2].to_string())
+                .collect();
+            assert_eq!(100, prefixes.len());
+        }
+    }
+
+    #[test]
+    fn test_exhaustion_increase_length() {
+        let mut gen = HaltonGenerator::with_alphabet(vec![0u8, 1], 2)
+            .exhaustion_strategy(ExhaustionStrategy::IncreaseLength);
+
+        for _ in 0..4 {
+            assert_eq!(2, gen.next_vec().len());
+        }
+        assert_eq!(3, gen.next_vec().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "HaltonGenerator exhausted: base=2 length=2 capacity=4")]
+    fn test_exhaustion_panic() {
+        let mut gen = HaltonGenerator::with_alphabet(vec![0u8, 1], 2);
+        for _ in 0..5 {
+            gen.next_vec();
+        }
+    }
+
+    #[test]
+    fn test_try_with_alphabet() {
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            HaltonGenerator::<u8>::try_with_alphabet(vec![], 3).err()
+        );
+
+        // 16^15 fits in a u64, but 16^16 doesn't.
+        let alphabet: Vec<u8> = (0..16).collect();
+        assert!(HaltonGenerator::try_with_alphabet(alphabet.clone(), 15).is_ok());
+        assert_eq!(
+            Some(AlphabetError::Overflow {
+                alphabet_len: 16,
+                length: 16
+            }),
+            HaltonGenerator::try_with_alphabet(alphabet, 16).err()
+        );
+    }
+}
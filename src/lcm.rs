@@ -12,7 +12,7 @@ fn factorize(mut n: u32) -> Vec<u32> {
     'outer: while n > 1 {
         let last = result.last().cloned();
         for i in last.unwrap_or(2)..n {
-            if n % i == 0 {
+            if n.is_multiple_of(i) {
                 if last != Some(i) {
                     result.push(i)
                 }
@@ -35,6 +35,30 @@ fn factorize(mut n: u32) -> Vec<u32> {
     result
 }
 
+/// Compute the prime factors of a given number in ascending order, with
+/// repetition (so that their product is `n`).
+///
+/// The moduli we factor are powers or products of alphabet sizes, so their
+/// prime factors are small and trial division is fast.
+pub fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut result = Vec::new();
+    let mut i = 2;
+
+    while i <= n / i {
+        while n.is_multiple_of(i) {
+            result.push(i);
+            n /= i;
+        }
+        i += 1;
+    }
+
+    if n > 1 {
+        result.push(n);
+    }
+
+    result
+}
+
 /// Generate the multiplier used for the linear congruent multiplier.
 /// `m_base` is assumed to be an n-th root of the actual `m`, with `n > 1`.
 /// This has the implication that if `m_base` is even, it is assumed that
@@ -48,14 +72,54 @@ pub fn generate_a(m_base: u32) -> u32 {
     // with l > 1, m_base being even implies that m_base is divisible by 4.
     // In these cases prod is already even, so we double it to make it
     // divisible by 4.
-    if m_base % 2 == 0 {
+    if m_base.is_multiple_of(2) {
         prod *= 2
     }
 
     prod + 1
 }
 
+/// Generate the multiplier used for a linear congruent multiplier whose
+/// modulus is the product of `radices`. Unlike `generate_a`, no assumption
+/// is made about the shape of `m`, so the factor of 4 is only added when `m`
+/// is actually divisible by 4.
+pub fn generate_a_mixed(radices: &[u32]) -> u64 {
+    let mut factors: Vec<u32> = radices.iter().flat_map(|&r| factorize(r)).collect();
+    factors.sort_unstable();
+    factors.dedup();
+
+    let mut prod = factors.into_iter().fold(1u64, |lhs, rhs| lhs * rhs as u64);
+    let m = radices.iter().fold(1u64, |lhs, &rhs| lhs * rhs as u64);
+
+    // As in `generate_a`, prod is already even if m is divisible by 4.
+    if m.is_multiple_of(4) {
+        prod *= 2
+    }
+
+    prod + 1
+}
+
+/// Compute the inverse of `a` modulo `m` using the extended Euclidean
+/// algorithm. Returns `None` if `a` and `m` are not coprime.
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (a as i128 % m as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+
+    if old_r != 1 {
+        return None;
+    }
+
+    Some(old_s.rem_euclid(m as i128) as u64)
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SerializedLcm"))]
 #[derive(Clone, Debug)]
 pub struct LinearCongruentMultiplier {
     /// The first value generated by this LCM.
@@ -74,6 +138,41 @@ pub struct LinearCongruentMultiplier {
     a: u64,
 
     exhausted: bool,
+
+    /// The number of steps from the first value to `next`, modulo `m`. This
+    /// is not serialized, since it can be recovered with `index_of`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    index: u64,
+}
+
+/// The serialized fields of a `LinearCongruentMultiplier`, from which the
+/// index is recovered on deserialization.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SerializedLcm {
+    first: u64,
+    next: u64,
+    m: u64,
+    c: u64,
+    a: u64,
+    exhausted: bool,
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedLcm> for LinearCongruentMultiplier {
+    fn from(fields: SerializedLcm) -> Self {
+        let mut lcm = Self {
+            first: fields.first,
+            next: fields.next,
+            m: fields.m,
+            c: fields.c,
+            a: fields.a,
+            exhausted: fields.exhausted,
+            index: 0,
+        };
+        lcm.index = lcm.index_of(lcm.next);
+        lcm
+    }
 }
 
 impl LinearCongruentMultiplier {
@@ -85,6 +184,7 @@ impl LinearCongruentMultiplier {
             c,
             a,
             exhausted: false,
+            index: 0,
         }
     }
 
@@ -93,7 +193,8 @@ impl LinearCongruentMultiplier {
     pub fn next(&mut self) -> u64 {
         let value = self.next;
 
-        self.next = (self.a * self.next + self.c) % self.m;
+        self.next = ((self.a as u128 * self.next as u128 + self.c as u128) % self.m as u128) as u64;
+        self.index = (self.index + 1) % self.m;
 
         if self.next == self.first {
             self.exhausted = true;
@@ -102,6 +203,177 @@ impl LinearCongruentMultiplier {
         value
     }
 
+    /// Undo the most recent call to `next`, so that the value it returned
+    /// will be returned again.
+    pub fn prev(&mut self) {
+        let a_inverse = mod_inverse(self.a, self.m)
+            .expect("Multiplier of a full-period LCM is coprime to the modulus.");
+        let m = self.m as u128;
+        let value = (self.next as u128 + m - self.c as u128 % m) % m;
+
+        if self.next == self.first {
+            self.exhausted = false;
+        }
+        self.next = (value * a_inverse as u128 % m) as u64;
+        self.index = (self.index + self.m - 1) % self.m;
+    }
+
+    /// Return the value `steps` steps after `value` in the sequence, in
+    /// `O(log steps)` time.
+    ///
+    /// This composes the affine step `x -> a * x + c` with itself by repeated
+    /// squaring, so it does not touch the internal state.
+    pub fn jump(&self, value: u64, steps: u64) -> u64 {
+        self.jump_u128(value, steps as u128)
+    }
+
+    /// Like `jump`, for step counts that don't fit in a `u64`.
+    fn jump_u128(&self, value: u64, steps: u128) -> u64 {
+        let m = self.m as u128;
+        let (mut acc_mult, mut acc_plus) = (1u128, 0u128);
+        let (mut cur_mult, mut cur_plus) = (self.a as u128 % m, self.c as u128 % m);
+        let mut steps = steps;
+
+        while steps > 0 {
+            if steps & 1 == 1 {
+                acc_mult = acc_mult * cur_mult % m;
+                acc_plus = (acc_plus * cur_mult + cur_plus) % m;
+            }
+            cur_plus = (cur_mult + 1) * cur_plus % m;
+            cur_mult = cur_mult * cur_mult % m;
+            steps >>= 1;
+        }
+
+        ((acc_mult * value as u128 + acc_plus) % m) as u64
+    }
+
+    /// Return the value generated `index` steps after the first value.
+    pub fn value_at(&self, index: u64) -> u64 {
+        self.jump(self.first, index)
+    }
+
+    /// Mark the LCM as not exhausted, so that `exhausted` reports on the
+    /// current cycle once the sequence has wrapped around.
+    pub fn restart_cycle(&mut self) {
+        self.exhausted = false;
+    }
+
+    /// Set the internal state so that the next value generated is the one
+    /// `index` steps after the first value.
+    pub fn seek(&mut self, index: u64) {
+        self.next = self.value_at(index % self.m);
+        self.index = index % self.m;
+        self.exhausted = index >= self.m;
+    }
+
+    /// Return the number of steps from the first value to `value`, i.e. the
+    /// inverse of `value_at`. Assumes the LCM has full period.
+    ///
+    /// For any divisor `d` of `m`, the sequence taken modulo `d` is itself a
+    /// full-period LCM, so the value at index `k` modulo `d` depends only on
+    /// `k` modulo `d`. We use this to recover the index one prime factor of
+    /// `m` at a time, trying each of the `p` possible next digits.
+    #[cfg(any(feature = "serde", test))]
+    pub fn index_of(&self, value: u64) -> u64 {
+        self.index_of_with_factors(value, &prime_factors(self.m))
+    }
+
+    /// Like `index_of`, but takes `prime_factors(self.m)` precomputed, so it
+    /// can be shared between lookups.
+    pub fn index_of_with_factors(&self, value: u64, factors: &[u64]) -> u64 {
+        let mut index = 0;
+        let mut modulus = 1;
+
+        for &p in factors {
+            let next_modulus = modulus * p;
+            let mut current = self.value_at(index);
+
+            for _ in 0..p {
+                if current % next_modulus == value % next_modulus {
+                    break;
+                }
+                current = self.jump(current, modulus);
+                index += modulus;
+            }
+
+            modulus = next_modulus;
+        }
+
+        index
+    }
+
+    /// Returns `true` if the parameters satisfy the Hull–Dobell theorem, so
+    /// that the LCM visits every value in `0..m` before repeating.
+    pub fn has_full_period(&self) -> bool {
+        let a_minus_1 = (self.a + self.m - 1) % self.m;
+        let (mut x, mut y) = (self.c, self.m);
+        while y != 0 {
+            (x, y) = (y, x % y);
+        }
+
+        x == 1
+            && prime_factors(self.m)
+                .into_iter()
+                .all(|p| a_minus_1.is_multiple_of(p))
+            && (!self.m.is_multiple_of(4) || a_minus_1.is_multiple_of(4))
+    }
+
+    /// Return the number of steps after which the sequence returns to its
+    /// first value. This is `m` when the Hull–Dobell conditions hold, and is
+    /// otherwise found from the factorization of `m` without walking the
+    /// sequence.
+    ///
+    /// Returns `0` if `a` shares a factor with `m`, in which case the
+    /// sequence is not a permutation and may never return to its first value.
+    pub fn period(&self) -> u64 {
+        if self.has_full_period() {
+            return self.m;
+        }
+
+        let mut factors = prime_factors(self.m);
+        if factors.iter().any(|&p| self.a.is_multiple_of(p)) {
+            return 0;
+        }
+
+        let mut primes = factors.clone();
+        primes.dedup();
+        let totient = primes.iter().fold(self.m, |t, &p| t / p * (p - 1));
+
+        // After `ord(a)` steps (a divisor of the totient) the map is a
+        // translation, which returns to the start after at most `m` more
+        // applications. So the period divides `m * totient`, and we find it
+        // by dividing out each prime factor while the multiple still returns
+        // to the first value.
+        factors.extend(prime_factors(totient));
+        let mut period = self.m as u128 * totient as u128;
+        for p in factors {
+            let candidate = period / p as u128;
+            if self.jump_u128(self.first, candidate) == self.first {
+                period = candidate;
+            }
+        }
+
+        period as u64
+    }
+
+    /// Return `(first, a, c)`, which together with `m` determine the
+    /// sequence.
+    pub fn params(&self) -> (u64, u64, u64) {
+        (self.first, self.a, self.c)
+    }
+
+    /// Return the value that the next call to `next` will return.
+    pub fn peek(&self) -> u64 {
+        self.next
+    }
+
+    /// Return the number of steps from the first value to the value that the
+    /// next call to `next` will return, modulo `m`. Unlike `index_of`, this
+    /// takes constant time.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
     /// Returns `true` iff the next value that will be generated is
     /// equal to the first value that was returned. This is true
     /// when the LCM is intitially created.
@@ -142,4 +414,143 @@ mod test {
         // Even.
         assert_eq!(53, generate_a(26));
     }
+
+    #[test]
+    fn test_jump() {
+        let lcm = LinearCongruentMultiplier::new(5, 3125, 1, generate_a(5) as u64);
+        let mut stepped = lcm.clone();
+
+        for i in 0..4000 {
+            assert_eq!(stepped.next(), lcm.value_at(i));
+        }
+
+        assert_eq!(lcm.jump(17, 123 + 456), lcm.jump(lcm.jump(17, 123), 456));
+        assert_eq!(17, lcm.jump(17, 3125));
+    }
+
+    #[test]
+    fn test_period() {
+        let lcm = LinearCongruentMultiplier::new(5, 3125, 1, generate_a(5) as u64);
+        assert_eq!(3125, lcm.period());
+
+        // Compare short periods against walking the sequence.
+        for (m, c, a) in [
+            (100, 1, 11),
+            (100, 5, 21),
+            (96, 3, 5),
+            (81, 1, 2),
+            (64, 1, 3),
+        ] {
+            for seed in [0, 7] {
+                let mut lcm = LinearCongruentMultiplier::new(seed, m, c, a);
+                let first = lcm.next();
+                let mut walked = 1;
+                while lcm.next() != first {
+                    walked += 1;
+                }
+
+                assert_eq!(walked, lcm.period(), "m={} c={} a={}", m, c, a);
+            }
+        }
+
+        // `a` isn't coprime to `m`.
+        assert_eq!(0, LinearCongruentMultiplier::new(1, 100, 1, 10).period());
+    }
+
+    #[test]
+    fn test_prime_factors() {
+        assert_eq!(Vec::<u64>::new(), prime_factors(1));
+        assert_eq!(vec![2, 2, 2], prime_factors(8));
+        assert_eq!(vec![2, 2, 3, 3, 3], prime_factors(108));
+        assert_eq!(vec![269], prime_factors(269));
+    }
+
+    #[test]
+    fn test_index_of() {
+        for (m_base, length) in [(5, 5), (6, 3), (36, 2), (2, 10), (7, 1)] {
+            let m = (m_base as u64).pow(length);
+            let lcm = LinearCongruentMultiplier::new(3 % m, m, 1, generate_a(m_base) as u64);
+
+            for i in 0..m {
+                assert_eq!(i, lcm.index_of(lcm.value_at(i)));
+            }
+        }
+
+        // Large moduli are handled without walking the sequence.
+        let m = 36u64.pow(12);
+        let lcm = LinearCongruentMultiplier::new(12345, m, 1, generate_a(36) as u64);
+        assert_eq!(m - 1, lcm.index_of(lcm.value_at(m - 1)));
+        assert_eq!(987654321, lcm.index_of(lcm.value_at(987654321)));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(Some(1), mod_inverse(1, 9));
+        assert_eq!(Some(5), mod_inverse(2, 9));
+        assert_eq!(Some(625), mod_inverse(53, 676));
+        assert_eq!(None, mod_inverse(6, 9));
+    }
+
+    #[test]
+    fn test_prev() {
+        let mut lcm = LinearCongruentMultiplier::new(5, 3125, 1, generate_a(5) as u64);
+
+        for _ in 0..3125 {
+            let value = lcm.next();
+            lcm.prev();
+            assert_eq!(value, lcm.next());
+        }
+
+        // Stepping back over the end of the cycle un-exhausts the LCM.
+        assert!(lcm.exhausted());
+        lcm.prev();
+        assert!(!lcm.exhausted());
+    }
+
+    #[test]
+    fn test_index() {
+        let mut lcm = LinearCongruentMultiplier::new(5, 3125, 1, generate_a(5) as u64);
+
+        for i in 0..3125 {
+            assert_eq!(i, lcm.index());
+            assert_eq!(lcm.index_of(lcm.peek()), lcm.index());
+            lcm.next();
+        }
+        assert_eq!(0, lcm.index());
+
+        lcm.prev();
+        assert_eq!(3124, lcm.index());
+        lcm.seek(1000);
+        assert_eq!(1000, lcm.index());
+        lcm.seek(3125);
+        assert_eq!(0, lcm.index());
+    }
+
+    #[test]
+    fn test_generate_a_mixed() {
+        // m = 6, not divisible by 4.
+        assert_eq!(7, generate_a_mixed(&[2, 3]));
+
+        // m = 12, divisible by 4 only across positions.
+        assert_eq!(13, generate_a_mixed(&[2, 2, 3]));
+
+        // m = 2600.
+        assert_eq!(261, generate_a_mixed(&[26, 10, 10]));
+
+        // Equivalent to generate_a for a fixed radix.
+        assert_eq!(generate_a(26) as u64, generate_a_mixed(&[26, 26]));
+    }
+
+    #[test]
+    fn test_has_full_period() {
+        assert!(LinearCongruentMultiplier::new(0, 100, 1, generate_a(10) as u64).has_full_period());
+        assert!(LinearCongruentMultiplier::new(0, 8, 1, 5).has_full_period());
+
+        // a - 1 must be divisible by 4 when m is.
+        assert!(!LinearCongruentMultiplier::new(0, 8, 1, 3).has_full_period());
+        // a - 1 must be divisible by every prime factor of m.
+        assert!(!LinearCongruentMultiplier::new(0, 100, 1, 11).has_full_period());
+        // c must be coprime to m.
+        assert!(!LinearCongruentMultiplier::new(0, 100, 5, 21).has_full_period());
+    }
 }
@@ -1,17 +1,35 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "capi")]
+pub mod capi;
+mod checksum;
+mod distribution;
+mod error;
+mod halton;
 mod lcm;
+mod mixed_radix;
+mod monotonic;
+#[cfg(feature = "signature")]
+mod signature;
+mod token;
+mod whitening;
 
+pub use distribution::CodeDistribution;
+pub use error::{AlphabetError, ParallelError, TokenError};
+use halton::reverse_digits;
+pub use halton::HaltonGenerator;
 use lcm::LinearCongruentMultiplier;
+pub use mixed_radix::MixedRadixGenerator;
+pub use monotonic::MonotonicGenerator;
 use rand_chacha::ChaCha12Rng;
 
-#[cfg(feature = "getrandom")]
 use rand_chacha::rand_core::SeedableRng;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use rand::Rng;
+use std::sync::Arc;
 
 /// Stores the state required to generate short codes, and implements short code generation.
 ///
@@ -22,29 +40,634 @@ use rand::Rng;
 /// ```
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
-pub struct ShortCodeGenerator<T: Copy> {
+pub struct ShortCodeGenerator<T: Clone> {
     lcm: LinearCongruentMultiplier,
     offset: u64,
-    alphabet: Vec<T>,
+
+    /// Shared between clones, since it never changes after construction.
+    alphabet: Arc<[T]>,
+
     length: u32,
-    exhaustion_strategy: ExhaustionStrategy,
-    
+    exhaustion_strategy: StrategySetting,
+
     /// Random number generator used to seed future LCMs if ExhaustionStrategy is
     /// ExtendLength. For other exhaustion strategies, it is set but never used because
     /// the initial LCM is never replaced.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, deserialize_with = "deserialize_rng")
+    )]
     rng: Option<ChaCha12Rng>,
-    
+
     /// Skip is used to enable partitioning. It forces the generator to skip
     /// over the given number of values between generated codes, enabling
     /// other partitions to use those codes.
     skip: Option<u32>,
-    
+
     /// When skip is in use, we do not want to skip the first value generated
     /// by an rng, so skip_after_next is initially false. When the first random
     /// value is generated, it is set to true, enabling the skip before subsequent
     /// random generations.
-    #[cfg_attr(feature = "serialize", serde(default))]
+    ///
+    /// Version 0.1.4 serialized this field as `used`.
+    #[cfg_attr(feature = "serialize", serde(default, alias = "used"))]
     skip_before_next: bool,
+
+    /// Limits the generator to a contiguous run of the sequence. Used by
+    /// [`ShortCodeGenerator::disjoint_partitions`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    block: Option<Block>,
+
+    /// Number of times the code length has been increased by
+    /// `ExhaustionStrategy::IncreaseLength`.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    growth_count: u32,
+
+    /// Number of times the sequence has wrapped around under
+    /// `ExhaustionStrategy::Cycle`.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    cycle_count: u64,
+
+    /// Caps the length that `ExhaustionStrategy::IncreaseLength` can grow
+    /// codes to. Set by [`ShortCodeGenerator::with_max_length`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    max_length: Option<MaxLength>,
+
+    /// Epoch prefixed to each code. Set by
+    /// [`ShortCodeGenerator::with_epoch`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    epoch: Option<Epoch>,
+
+    /// Increment of the LCM, if set by [`ShortCodeGenerator::with_increment`].
+    /// Otherwise it is 1.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    increment: Option<u64>,
+
+    /// The number of codes this generator has emitted, net of codes returned
+    /// by `unget` or `rev`.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    generated_count: u64,
+
+    /// Partition index rendered in front of each code. Set by
+    /// [`ShortCodeGenerator::with_partition_marker`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    partition_marker: Option<u32>,
+
+    /// Whether values are permuted before rendering. Set by
+    /// [`ShortCodeGenerator::with_output_whitening`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    whitening: bool,
+
+    /// How the sequence of integers rendered as codes is produced. Set by
+    /// [`ShortCodeGenerator::integer_source`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    integer_source: IntegerSource,
+
+    /// Whether a check symbol is appended to each code. Set by
+    /// [`ShortCodeGenerator::with_check_symbol`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    check_symbol: bool,
+
+    /// Width that codes are padded to with the first symbol of the alphabet.
+    /// Set by [`ShortCodeGenerator::with_display_width`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    display_width: Option<u32>,
+
+    /// Key and length of the signature appended to each code. Set by
+    /// [`ShortCodeGenerator::with_signature`].
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, deserialize_with = "deserialize_signature")
+    )]
+    signature: Option<Signature>,
+
+    /// Called once when `ExhaustionStrategy::WarnThenPanic` enters its warning
+    /// zone. Set by [`ShortCodeGenerator::with_exhaustion_warning`]; not
+    /// serialized.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    warning_hook: Option<fn(u64)>,
+
+    /// Per-position restrictions on generated codes. Codes that violate them
+    /// are skipped.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    constraints: Vec<PositionConstraint>,
+
+    /// The sequences used at earlier lengths, oldest first, for
+    /// [`ShortCodeGenerator::possible_indices`].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    past_sequences: Vec<PastSequence>,
+
+    /// Version of the state format, set to `FORMAT_VERSION` when the
+    /// generator is created or its length increases. State from a later
+    /// version of the crate fails to deserialize rather than silently
+    /// generating different codes.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, deserialize_with = "deserialize_format_version")
+    )]
+    format_version: u8,
+}
+
+/// Deserialize the format version, rejecting versions newer than this crate
+/// understands.
+#[cfg(feature = "serde")]
+fn deserialize_format_version<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u8, D::Error> {
+    let version = u8::deserialize(deserializer)?;
+    if version > FORMAT_VERSION {
+        return Err(serde::de::Error::custom(format!(
+            "Unsupported state format version {} (expected at most {}).",
+            version, FORMAT_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// Deserialize the rng in the same format as `ChaCha12Rng` itself, checking
+/// in debug builds that the stored `word_pos` survives being restored. The
+/// rng only tracks a 68-bit word position, so a hand-edited or corrupted
+/// state could otherwise load silently with a different rng than it names.
+#[cfg(feature = "serde")]
+fn deserialize_rng<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<ChaCha12Rng>, D::Error> {
+    /// Mirrors the serialized form of `ChaCha12Rng`.
+    #[derive(Deserialize)]
+    struct Repr {
+        seed: [u8; 32],
+        stream: u64,
+        word_pos: u128,
+    }
+
+    let repr = match Option::<Repr>::deserialize(deserializer)? {
+        Some(repr) => repr,
+        None => return Ok(None),
+    };
+
+    let mut rng = ChaCha12Rng::from_seed(repr.seed);
+    rng.set_stream(repr.stream);
+    rng.set_word_pos(repr.word_pos);
+
+    debug_assert_eq!(
+        repr.word_pos,
+        rng.get_word_pos(),
+        "Deserialized rng word_pos is out of range."
+    );
+
+    Ok(Some(rng))
+}
+
+/// The key and number of symbols of the signature appended to each code.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+struct Signature {
+    /// Secret, so it is neither serialized nor shown by `Debug`. `None` after
+    /// deserializing, until the key is set again with
+    /// [`ShortCodeGenerator::with_signature`].
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    key: Option<Vec<u8>>,
+
+    symbols: u32,
+}
+
+impl std::fmt::Debug for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signature")
+            .field("key", &self.key.as_ref().map(|_| "<redacted>"))
+            .field("symbols", &self.symbols)
+            .finish()
+    }
+}
+
+/// Deserialize the signature settings, failing if the `signature` feature
+/// is off, since the generator couldn't sign its codes.
+#[cfg(feature = "serde")]
+fn deserialize_signature<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Signature>, D::Error> {
+    let signature = Option::<Signature>::deserialize(deserializer)?;
+
+    #[cfg(not(feature = "signature"))]
+    if signature.is_some() {
+        return Err(serde::de::Error::custom(
+            "Need crate feature signature to deserialize a generator with signatures.",
+        ));
+    }
+
+    Ok(signature)
+}
+
+/// The sequence a generator used before increasing its length.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+struct PastSequence {
+    length: u32,
+    lcm: LinearCongruentMultiplier,
+    offset: u64,
+}
+
+/// A caller-advanced epoch, rendered in front of each code.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
+struct Epoch {
+    /// Number of symbols used to render the epoch.
+    symbols: u32,
+
+    value: u64,
+}
+
+/// A contiguous run of a generator's sequence, handed out by
+/// [`ShortCodeGenerator::reserve_block`] and turned into a generator by
+/// [`ShortCodeGenerator::from_block`].
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockReservation {
+    /// Index in the sequence of the first value of the block.
+    pub start: u64,
+
+    /// Number of values in the block.
+    pub count: u64,
+
+    /// Code length of the generator the block was reserved from.
+    pub length: usize,
+}
+
+/// Everything that determines the sequence of codes of a generator, except
+/// where in the sequence it is. Created by [`ShortCodeGenerator::schema`] and
+/// turned back into a generator by [`ShortCodeGenerator::restore`].
+///
+/// This is meant to be shared between services that must agree on the
+/// format and order of codes, while each keeps its own cursor.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeSchema<T> {
+    alphabet: Vec<T>,
+    length: usize,
+
+    /// First value of the sequence, i.e. at position zero.
+    first: u64,
+    a: u64,
+    c: u64,
+    m: u64,
+    offset: u64,
+
+    #[cfg_attr(feature = "serialize", serde(default))]
+    whitening: bool,
+
+    #[cfg_attr(feature = "serialize", serde(default))]
+    integer_source: IntegerSource,
+}
+
+impl<T> CodeSchema<T> {
+    /// Return the alphabet of codes.
+    pub fn alphabet(&self) -> &[T] {
+        &self.alphabet
+    }
+
+    /// Return the length of codes, excluding any prefix or check symbol.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+/// How [`ShortCodeGenerator::format_index`] renders a code for display.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Text written before the code, e.g. `"INV-"`.
+    pub prefix: String,
+
+    /// Number of symbols in each group, or `0` to not group symbols.
+    pub group_size: usize,
+
+    /// Written between groups of symbols.
+    pub separator: char,
+
+    /// Whether to append a check symbol, computed as for
+    /// [`ShortCodeGenerator::with_check_symbol`].
+    pub checksum: bool,
+}
+
+impl Default for FormatOptions {
+    /// No prefix, grouping or check symbol, with `-` as the separator.
+    fn default() -> Self {
+        FormatOptions {
+            prefix: String::new(),
+            group_size: 0,
+            separator: '-',
+            checksum: false,
+        }
+    }
+}
+
+/// The result of [`ShortCodeGenerator::validate_full`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationResult {
+    /// Whether the code has the length of generated codes, including any
+    /// epoch prefix and check symbol.
+    pub length_ok: bool,
+
+    /// Whether every symbol of the code is in the alphabet.
+    pub in_alphabet: bool,
+
+    /// Whether the check symbol matches, or `None` if check symbols are not
+    /// enabled.
+    pub checksum_ok: Option<bool>,
+
+    /// The partition that issues the code, or `None` if the code has the
+    /// wrong length or symbols.
+    pub partition: Option<u32>,
+}
+
+impl ValidationResult {
+    /// Returns `true` if every check passed.
+    pub fn is_valid(&self) -> bool {
+        self.length_ok && self.in_alphabet && self.checksum_ok != Some(false)
+    }
+}
+
+/// The symbols of a generator's alphabet, for
+/// [`ShortCodeGenerator::build`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet<T>(pub Vec<T>);
+
+/// The number of symbols in a generated code, for
+/// [`ShortCodeGenerator::build`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Length(pub usize);
+
+/// Version of the serialized state written by this version of the crate.
+/// State serialized before versions were recorded deserializes as version 0.
+const FORMAT_VERSION: u8 = 1;
+
+/// Number of consecutive codes that position constraints can reject before
+/// generation gives up.
+const MAX_CONSTRAINT_REJECTIONS: u32 = 100_000;
+
+/// Utilization at which `ExhaustionStrategy::WarnThenPanic` warns.
+const WARNING_THRESHOLD: f64 = 0.99;
+
+/// Number of codes that [`ShortCodeGenerator::next_distinct_prefix`] skips
+/// before returning a code whose prefix isn't distinct.
+const MAX_PREFIX_ATTEMPTS: u32 = 1_000;
+
+/// Number of consecutive pairs of codes that
+/// [`ShortCodeGenerator::consecutive_symbol_change`] compares.
+const SYMBOL_CHANGE_SAMPLE: u64 = 10_000;
+
+/// Restricts the symbol at one position of generated codes. Set by
+/// [`ShortCodeGenerator::with_position_constraint`].
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+struct PositionConstraint {
+    position: u32,
+
+    /// Whether each symbol of the alphabet, by index, is allowed.
+    allowed: Vec<bool>,
+
+    /// Whether the constraint was set by
+    /// [`ShortCodeGenerator::with_banned_leading`], and so must stay on the
+    /// first rendered symbol.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    leading: bool,
+}
+
+/// A cap on code length, and what to do instead of growing past it.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
+struct MaxLength {
+    length: u32,
+    fallback: ExhaustionStrategy,
+}
+
+/// Check that `alphabet` has at least two symbols, none of them repeated,
+/// whitespace or control characters.
+#[cfg(feature = "getrandom")]
+fn check_symbols(alphabet: &[char]) -> Result<(), AlphabetError> {
+    match alphabet.len() {
+        0 => return Err(AlphabetError::Empty),
+        1 => return Err(AlphabetError::SingleSymbol),
+        _ => {}
+    }
+
+    for (i, &symbol) in alphabet.iter().enumerate() {
+        if symbol.is_whitespace() || symbol.is_control() {
+            return Err(AlphabetError::InvalidSymbol { symbol });
+        }
+        if alphabet[..i].contains(&symbol) {
+            return Err(AlphabetError::DuplicateSymbol { symbol });
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that `alphabet` has at least two symbols, none of them repeated.
+fn check_distinct<T: PartialEq>(alphabet: &[T]) -> Result<(), AlphabetError> {
+    match alphabet.len() {
+        0 => return Err(AlphabetError::Empty),
+        1 => return Err(AlphabetError::SingleSymbol),
+        _ => {}
+    }
+
+    match (1..alphabet.len()).find(|&i| alphabet[..i].contains(&alphabet[i])) {
+        Some(index) => Err(AlphabetError::RepeatedSymbol { index }),
+        None => Ok(()),
+    }
+}
+
+/// Returns `true` if `c` is in one of the common ranges of characters that
+/// combine with the character before or after them when rendered.
+#[cfg(feature = "getrandom")]
+fn is_combining(c: char) -> bool {
+    matches!(
+        c as u32,
+        // Combining diacritical marks, and their extensions and supplement.
+        0x0300..=0x036F
+            | 0x1AB0..=0x1AFF
+            | 0x1DC0..=0x1DFF
+            | 0x20D0..=0x20FF
+            | 0xFE20..=0xFE2F
+            // Hebrew and Arabic points.
+            | 0x0591..=0x05C7
+            | 0x0610..=0x061A
+            | 0x064B..=0x065F
+            // Japanese voiced sound marks.
+            | 0x3099..=0x309A
+            // Hangul vowels and final consonants, which join a leading consonant.
+            | 0x1160..=0x11FF
+            // Zero-width non-joiner and joiner.
+            | 0x200C..=0x200D
+            // Variation selectors.
+            | 0xFE00..=0xFE0F
+            | 0xE0100..=0xE01EF
+            // Regional indicators, which pair up into flags.
+            | 0x1F1E6..=0x1F1FF
+            // Emoji skin tone modifiers.
+            | 0x1F3FB..=0x1F3FF
+            // Tags.
+            | 0xE0020..=0xE007F
+    )
+}
+
+/// Render `value` as `length` symbols of `alphabet`, treating it as a base-N
+/// number with the least significant digit first.
+fn render<T: Clone>(value: u64, alphabet: &[T], length: u32) -> Vec<T> {
+    digits(value, alphabet.len() as u64, length)
+        .into_iter()
+        .map(|digit| alphabet[digit as usize].clone())
+        .collect()
+}
+
+/// Return the `length` least significant base-`base` digits of `value`,
+/// least significant first.
+fn digits(mut value: u64, base: u64, length: u32) -> Vec<u64> {
+    let mut result = Vec::with_capacity(length as usize);
+
+    for _ in 0..length {
+        result.push(value % base);
+        value /= base;
+    }
+
+    result
+}
+
+/// Return the number of codes of `length` symbols from an alphabet of
+/// `alphabet_len` symbols, or an error if it doesn't fit in a `u64`.
+fn checked_capacity(alphabet_len: usize, length: usize) -> Result<u64, AlphabetError> {
+    if alphabet_len == 0 {
+        return Err(AlphabetError::Empty);
+    }
+
+    let overflow = AlphabetError::Overflow {
+        alphabet_len,
+        length,
+    };
+
+    // The LCM multiplier is computed from the alphabet size as a u32.
+    u32::try_from(alphabet_len).map_err(|_| overflow.clone())?;
+    let length_u32 = u32::try_from(length).map_err(|_| overflow.clone())?;
+
+    (alphabet_len as u64)
+        .checked_pow(length_u32)
+        .ok_or(overflow)
+}
+
+/// Return the smallest code length (of at least 1) for which an alphabet of
+/// `alphabet_len` symbols provides at least `min_capacity` codes.
+///
+/// Returns [`AlphabetError::Overflow`] if the required number of codes can't
+/// be reached without overflowing a `u64`.
+pub fn min_length_for(alphabet_len: usize, min_capacity: u64) -> Result<usize, AlphabetError> {
+    let mut length = 1;
+    let mut capacity = checked_capacity(alphabet_len, length)?;
+
+    if alphabet_len == 1 && min_capacity > 1 {
+        return Err(AlphabetError::SingleSymbol);
+    }
+
+    while capacity < min_capacity {
+        length += 1;
+        capacity = checked_capacity(alphabet_len, length)?;
+    }
+
+    Ok(length)
+}
+
+/// Concatenate `parts` into one alphabet, dropping repeated symbols and
+/// keeping the first occurrence of each. For example, merging digits with a
+/// set of letters that also contains some digits gives each symbol once, so
+/// none is twice as likely as the others.
+///
+/// ```
+/// let alphabet = tiny_id::merge_alphabets(&[&['0', '1', '2'], &['a', '1', 'b']]);
+/// assert_eq!(vec!['0', '1', '2', 'a', 'b'], alphabet);
+/// ```
+pub fn merge_alphabets<T: Clone + PartialEq>(parts: &[&[T]]) -> Vec<T> {
+    let mut alphabet: Vec<T> = Vec::new();
+    for symbol in parts.iter().flat_map(|part| part.iter()) {
+        if !alphabet.contains(symbol) {
+            alphabet.push(symbol.clone());
+        }
+    }
+    alphabet
+}
+
+/// Return the number of distinct codes that `partitions` generators created
+/// by [`ShortCodeGenerator::into_partitioned_generators`] (or
+/// [`ShortCodeGenerator::disjoint_partitions`]) can issue between them
+/// before any code repeats, for codes of `length` symbols from an alphabet
+/// of `alphabet_size`.
+///
+/// Partitions divide a single shared code space between them rather than
+/// each getting a space of their own, so this is the capacity of one
+/// generator, `alphabet_size ^ length`, not `partitions` times that.
+///
+/// Returns `None` if the capacity overflows a `u64`, or if the space can't
+/// be split into `partitions` partitions (see
+/// [`ShortCodeGenerator::try_into_partitioned_generators`]).
+pub fn fleet_capacity(alphabet_size: u64, length: u32, partitions: u32) -> Option<u64> {
+    let capacity = alphabet_size.checked_pow(length)?;
+    (partitions > 0 && partitions as u64 <= capacity).then_some(capacity)
+}
+
+/// Compute `(lhs + rhs) % m` without overflowing.
+fn add_mod(lhs: u64, rhs: u64, m: u64) -> u64 {
+    ((lhs as u128 + rhs as u128) % m as u128) as u64
+}
+
+/// Draw the seed and offset of a new LCM with modulus `m` (which must be a
+/// power of `alphabet_size`) and increment `c` from `rng`.
+fn seed_lcm(
+    alphabet_size: u32,
+    m: u64,
+    c: u64,
+    rng: &mut ChaCha12Rng,
+) -> (LinearCongruentMultiplier, u64) {
+    use lcm::generate_a;
+
+    let a = generate_a(alphabet_size) as u64;
+    let lcm_seed = rng.gen_range(0..m);
+    let offset = rng.gen_range(0..m);
+
+    (LinearCongruentMultiplier::new(lcm_seed, m, c, a), offset)
+}
+
+/// A contiguous run of an LCM's sequence, which a generator treats as its
+/// entire code space.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug)]
+struct Block {
+    /// Sequence index (relative to the first value of the LCM) at which the
+    /// block starts.
+    start: u64,
+
+    /// Number of values in the block.
+    len: u64,
+
+    /// Number of values in the block that have already been stepped past.
+    used: u64,
+
+    /// If the block is partition `index` of `total` disjoint partitions, this
+    /// is `(index, total)`. This lets the block be recomputed when the length
+    /// increases.
+    partition: Option<(u32, u32)>,
+}
+
+impl Block {
+    /// Return partition `index` of `total` roughly equal, contiguous blocks
+    /// covering a sequence of length `m`.
+    fn partition(index: u32, total: u32, m: u64) -> Self {
+        let bound = |i: u32| (i as u128 * m as u128 / total as u128) as u64;
+        let start = bound(index);
+
+        Block {
+            start,
+            len: bound(index + 1) - start,
+            used: 0,
+            partition: Some((index, total)),
+        }
+    }
 }
 
 impl ShortCodeGenerator<char> {
@@ -54,230 +677,5890 @@ impl ShortCodeGenerator<char> {
         Self::with_alphabet("0123456789".chars().collect(), length)
     }
 
-    /// Create a short code generator using lowercase alphanumeric characters.
-    #[cfg(feature = "getrandom")]
-    pub fn new_lowercase_alphanumeric(length: usize) -> Self {
-        Self::with_alphabet(
-            "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect(),
-            length,
-        )
+    /// Create a short code generator using lowercase alphanumeric characters.
+    #[cfg(feature = "getrandom")]
+    pub fn new_lowercase_alphanumeric(length: usize) -> Self {
+        Self::with_alphabet(
+            "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect(),
+            length,
+        )
+    }
+
+    /// Create a short code generator using upper and lowercase alphanumeric characters.
+    #[cfg(feature = "getrandom")]
+    pub fn new_alphanumeric(length: usize) -> Self {
+        Self::with_alphabet(
+            "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
+                .chars()
+                .collect(),
+            length,
+        )
+    }
+
+    /// Create a short code generator using uppercase characters.
+    #[cfg(feature = "getrandom")]
+    pub fn new_uppercase(length: usize) -> Self {
+        Self::with_alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect(), length)
+    }
+
+    /// Create a short code generator using the characters from `start` to
+    /// `end` (inclusive) as the alphabet, e.g. `new_char_range('a', 'z', 5)`.
+    ///
+    /// Panics if `start > end`.
+    #[cfg(feature = "getrandom")]
+    pub fn new_char_range(start: char, end: char, length: usize) -> Self {
+        assert!(
+            start <= end,
+            "Expected start of character range ({:?}) to not be after its end ({:?}).",
+            start,
+            end
+        );
+        Self::with_alphabet((start..=end).collect(), length)
+    }
+
+    /// Create a short code generator using the characters of `s` as the
+    /// alphabet, e.g. an alphabet read from a config file.
+    ///
+    /// Returns an error if `s` has fewer than two characters, repeats a
+    /// character, or contains whitespace or control characters, as well as
+    /// in the cases described in [`ShortCodeGenerator::try_with_alphabet`].
+    #[cfg(feature = "getrandom")]
+    pub fn try_from_alphabet_str(s: &str, length: usize) -> Result<Self, AlphabetError> {
+        let alphabet: Vec<char> = s.chars().collect();
+        check_symbols(&alphabet)?;
+        Self::try_with_alphabet(alphabet, length)
+    }
+
+    /// Create a short code generator from an alphabet of arbitrary Unicode
+    /// characters, e.g. a set of CJK characters for localized codes.
+    ///
+    /// Each `char` is a single Unicode scalar value, and `next_string`
+    /// renders a code as the concatenation of its scalars, with no
+    /// normalization. To make sure that this reads as the same number of
+    /// characters as the code has symbols, this panics if the alphabet
+    /// contains a character that combines with its neighbors (such as a
+    /// combining accent, zero-width joiner, variation selector or emoji
+    /// modifier), as well as in the cases described in
+    /// [`ShortCodeGenerator::try_from_alphabet_str`]. Combining characters
+    /// are recognized from the common Unicode blocks that contain them,
+    /// not the full Unicode database.
+    #[cfg(feature = "getrandom")]
+    pub fn new_unicode(alphabet: &[char], length: usize) -> Self {
+        let result = check_symbols(alphabet).and_then(|()| {
+            match alphabet.iter().find(|&&symbol| is_combining(symbol)) {
+                Some(&symbol) => Err(AlphabetError::CombiningSymbol { symbol }),
+                None => Self::try_with_alphabet(alphabet.to_vec(), length),
+            }
+        });
+        result.unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Return the next short code, represented as a string.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_string(&mut self) -> String {
+        self.next_vec().into_iter().collect()
+    }
+
+    /// Return the next short code as a string, with its symbols in reverse
+    /// order (most significant symbol first).
+    ///
+    /// This is purely cosmetic: reversing is a bijection, so codes are still
+    /// unique, but the symbols that vary most between consecutive codes are
+    /// moved to the end. Use it consistently, since a reversed code is not
+    /// the code `next_string` would have returned. All `next_*` calls are
+    /// equivalent to each other in terms of the resulting state of self.
+    pub fn next_string_reversed(&mut self) -> String {
+        self.next_vec().into_iter().rev().collect()
+    }
+
+    /// Return the index in its cycle of every code that `code` could be:
+    /// a code of the current length (as for
+    /// [`ShortCodeGenerator::decode_vec`]), or a code of an earlier length,
+    /// left-padded with the first symbol of the alphabet, as it would be
+    /// stored in a fixed-width field or shown with
+    /// [`ShortCodeGenerator::with_display_width`]. For example, after
+    /// growing from length 1 to 2, `"07"` is both a code of length 2 and the
+    /// padded code `"7"`.
+    ///
+    /// Indices at the current length come first, followed by those at
+    /// earlier lengths, most recent first; each is the position in the cycle
+    /// of the length it was found at. `code` is a code without any epoch
+    /// prefix, check symbol or signature. This is meant for auditing
+    /// ambiguous codes after a length increase; under
+    /// [`ExhaustionStrategy::IncreaseLengthPadded`], at most one index is
+    /// returned for codes this generator issued.
+    pub fn possible_indices(&self, code: &str) -> Vec<u64> {
+        let code: Vec<char> = code.chars().collect();
+        let mut indices: Vec<u64> = self.decode_vec(&code).into_iter().collect();
+
+        for past in self.past_sequences.iter().rev() {
+            let Some(padding) = code.len().checked_sub(past.length as usize) else {
+                continue;
+            };
+            if !code[..padding]
+                .iter()
+                .all(|&symbol| symbol == self.alphabet[0])
+            {
+                continue;
+            }
+
+            let mut earlier = self.clone();
+            earlier.lcm = past.lcm.clone();
+            earlier.offset = past.offset;
+            earlier.length = past.length;
+            indices.extend(earlier.decode_vec(&code[padding..]));
+        }
+
+        indices
+    }
+
+    /// Move the generator into a closure that returns the next short code
+    /// each time it is called, for APIs that take a factory such as
+    /// `FnMut() -> String`.
+    pub fn into_string_fn(mut self) -> impl FnMut() -> String {
+        move || self.next_string()
+    }
+
+    /// Render the code at `index` in the current cycle (see
+    /// [`ShortCodeGenerator::code_at`]) for display, with the prefix,
+    /// grouping and check symbol given by `opts`. For example, with a prefix
+    /// of `"ID-"`, groups of 3 and a check symbol, a code of length 5 renders
+    /// like `"ID-k3x-9qa"`. Groups are counted from the start of the code,
+    /// and the check symbol is grouped with the rest of the code.
+    ///
+    /// Returns `None` if `index` is not less than
+    /// [`ShortCodeGenerator::capacity`].
+    pub fn format_index(&self, index: u64, opts: FormatOptions) -> Option<String> {
+        if index >= self.lcm.m {
+            return None;
+        }
+
+        let mut symbols = self.code_at(index);
+        if opts.checksum {
+            let digits: Vec<u64> = symbols
+                .iter()
+                .map(|symbol| self.alphabet.iter().position(|s| s == symbol).unwrap() as u64)
+                .collect();
+            let check = checksum::check_digit(&digits, self.alphabet.len() as u64);
+            symbols.push(self.alphabet[check as usize]);
+        }
+
+        let mut result = opts.prefix;
+        for (i, symbol) in symbols.into_iter().enumerate() {
+            if opts.group_size > 0 && i > 0 && i % opts.group_size == 0 {
+                result.push(opts.separator);
+            }
+            result.push(symbol);
+        }
+
+        Some(result)
+    }
+
+    /// Check a code received from a user in one call: whether its length
+    /// and symbols are right, whether its check symbol (if enabled) matches,
+    /// and which of `total_partitions` partitions created by
+    /// [`ShortCodeGenerator::into_partitioned_generators`] issues it.
+    pub fn validate_full(&self, code: &str, total_partitions: u32) -> ValidationResult {
+        let code: Vec<char> = code.chars().collect();
+
+        let length_ok = self.accepts_length(code.len());
+        let in_alphabet = code.iter().all(|c| self.alphabet.contains(c));
+        let checksum_ok = self
+            .check_symbol
+            .then(|| self.has_valid_check_symbol(&code));
+        let partition = match self.strip_code(&code) {
+            Some(_) if self.partition_marker.is_some() && in_alphabet => {
+                self.partition_of(&code, total_partitions)
+            }
+            Some(core) if in_alphabet && total_partitions > 0 => {
+                self.partition_of(core, total_partitions)
+            }
+            _ => None,
+        };
+
+        ValidationResult {
+            length_ok,
+            in_alphabet,
+            checksum_ok,
+            partition,
+        }
+    }
+
+    /// Append `sig_symbols` signature symbols to each code, so that codes can
+    /// be checked for tampering with [`ShortCodeGenerator::verify_signature`]
+    /// without looking them up. Without `key`, finding a valid code other
+    /// than by guessing takes about `alphabet_len ^ sig_symbols` attempts.
+    ///
+    /// The signature is HMAC-SHA256 keyed with `key`, computed over the rest
+    /// of the code (including any prefix and check symbol) as the index in
+    /// the alphabet of each symbol, encoded as a 4-byte big-endian integer.
+    /// The MAC, read as a big-endian integer, is written in base
+    /// `alphabet_len` and its `sig_symbols` least significant digits are
+    /// appended, least significant first.
+    ///
+    /// `key` must be kept secret: anyone who has it can sign codes. It is not
+    /// part of the serialized state of the generator, nor of its `Debug`
+    /// output. After deserializing, call this again with the same key and
+    /// `sig_symbols`; until then, generating a code panics and
+    /// [`ShortCodeGenerator::verify_signature`] returns `false`.
+    ///
+    /// Requires the `signature` crate feature. Panics if `sig_symbols` is
+    /// zero, or more than the 256 bits of the MAC can fill. Preserves other
+    /// state.
+    #[cfg(feature = "signature")]
+    pub fn with_signature(mut self, key: Vec<u8>, sig_symbols: usize) -> Self {
+        let max = signature::max_digits(self.alphabet.len() as u64);
+        assert!(
+            sig_symbols > 0 && sig_symbols <= max,
+            "Expected between 1 and {} signature symbols.",
+            max
+        );
+
+        self.signature = Some(Signature {
+            key: Some(key),
+            symbols: sig_symbols as u32,
+        });
+        self
+    }
+
+    /// Returns `true` if `code` (as generated by `next_string`) ends in the
+    /// signature of the rest of the code. Always returns `false` if
+    /// signatures are not enabled with [`ShortCodeGenerator::with_signature`]
+    /// (including when the key hasn't been set again after deserializing), or
+    /// if `code` has the wrong length or symbols that aren't in the alphabet.
+    #[cfg(feature = "signature")]
+    pub fn verify_signature(&self, code: &str) -> bool {
+        let Some((key, sig)) = self
+            .signature
+            .as_ref()
+            .and_then(|sig| Some((sig.key.as_ref()?, sig)))
+        else {
+            return false;
+        };
+
+        let digits: Option<Vec<u64>> = code
+            .chars()
+            .map(|symbol| Some(self.alphabet.iter().position(|s| *s == symbol)? as u64))
+            .collect();
+        let Some(digits) = digits.filter(|digits| self.accepts_length(digits.len())) else {
+            return false;
+        };
+
+        let (signed, signature) = digits.split_at(digits.len() - sig.symbols as usize);
+        signature::verify(key, signed, self.alphabet.len() as u64, signature)
+    }
+
+    /// Write the next short code to `w` as UTF-8, without allocating a `String`.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    #[cfg(feature = "std")]
+    pub fn next_write<W: std::io::Write>(&mut self, w: &mut W) -> std::io::Result<()> {
+        let mut buf = [0; 4];
+        for c in self.next_vec() {
+            w.write_all(c.encode_utf8(&mut buf).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Write the next `n` short codes to `w`, separated by the byte `sep`.
+    /// No separator is written after the last code.
+    ///
+    /// Writes are small, so `w` should usually be buffered (e.g. with
+    /// [`std::io::BufWriter`]).
+    #[cfg(feature = "std")]
+    pub fn write_n<W: std::io::Write>(
+        &mut self,
+        n: usize,
+        sep: u8,
+        w: &mut W,
+    ) -> std::io::Result<()> {
+        for i in 0..n {
+            if i > 0 {
+                w.write_all(&[sep])?;
+            }
+            self.next_write(w)?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a generator of lowercase alphanumeric codes of length 8, as with
+/// `ShortCodeGenerator::new_lowercase_alphanumeric(8)`. That is about 2.8
+/// trillion codes before the length increases. These defaults won't change
+/// in a minor version.
+#[cfg(feature = "getrandom")]
+impl Default for ShortCodeGenerator<char> {
+    fn default() -> Self {
+        Self::new_lowercase_alphanumeric(8)
+    }
+}
+
+impl ShortCodeGenerator<&str> {
+    /// Emoji used by [`ShortCodeGenerator::new_emoji`]. Some are made of more
+    /// than one `char` (e.g. with a variation selector), which is why symbols
+    /// are `&str` rather than `char`.
+    pub const EMOJI: [&'static str; 32] = [
+        "😀", "😎", "🤖", "👻", "🐶", "🐱", "🦊", "🐻", "🐼", "🐸", "🐙", "🦋", "🌵", "🌻", "🍄",
+        "🍎", "🍋", "🍉", "🍒", "🥑", "🍕", "🍩", "☕️", "⚽️", "🎸", "🎲", "🚀", "⛵️", "🌙", "⭐️",
+        "❤️", "🔥",
+    ];
+
+    /// Create a short code generator whose symbols are the emoji in
+    /// [`ShortCodeGenerator::EMOJI`].
+    ///
+    /// Any alphabet of string symbols can be used the same way through
+    /// [`ShortCodeGenerator::with_alphabet`].
+    #[cfg(feature = "getrandom")]
+    pub fn new_emoji(length: usize) -> Self {
+        Self::with_alphabet(Self::EMOJI.to_vec(), length)
+    }
+
+    /// Return the next short code, with its symbols concatenated into a string.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_string(&mut self) -> String {
+        self.next_vec().concat()
+    }
+}
+
+impl ShortCodeGenerator<String> {
+    /// Return the next short code, with its symbols concatenated into a string.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_string(&mut self) -> String {
+        self.next_vec().concat()
+    }
+}
+
+impl<T: Clone> ShortCodeGenerator<T> {
+    /// Split this generator into `generators` generators which together emit the
+    /// same sequence of codes as this one would, with no overlap between them.
+    ///
+    /// Returns an empty `Vec` if `generators` is zero. Panics if this
+    /// generator is already a partition, or if `generators` is greater than
+    /// [`ShortCodeGenerator::capacity`], since some partitions would have no
+    /// codes to generate. See
+    /// [`ShortCodeGenerator::try_into_partitioned_generators`] for a version
+    /// that returns an error instead.
+    pub fn into_partitioned_generators(self, generators: u32) -> Vec<Self> {
+        if generators == 0 {
+            return Vec::new();
+        }
+
+        self.try_into_partitioned_generators(generators)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`ShortCodeGenerator::into_partitioned_generators`], but returns
+    /// an error if the generator was already created by
+    /// `into_partitioned_generators` or
+    /// [`ShortCodeGenerator::disjoint_partitions`], if `generators` is zero,
+    /// or if it is greater than [`ShortCodeGenerator::capacity`].
+    pub fn try_into_partitioned_generators(
+        self,
+        generators: u32,
+    ) -> Result<Vec<Self>, ParallelError> {
+        if self.skip.is_some() || self.block.is_some() {
+            return Err(ParallelError::AlreadyParallel);
+        }
+        if generators == 0 {
+            return Err(ParallelError::ZeroPartitions);
+        }
+        if generators as u64 > self.lcm.m {
+            return Err(ParallelError::ExceedsCapacity {
+                partitions: generators,
+                capacity: self.lcm.m,
+            });
+        }
+
+        let skip = generators - 1;
+
+        Ok((0..generators)
+            .map(move |offset| {
+                let mut gen = self.clone();
+
+                for _ in 0..offset {
+                    gen.step();
+                }
+                gen.skip_before_next = false;
+                gen.skip = Some(skip);
+
+                gen
+            })
+            .collect())
+    }
+
+    /// Turn this generator into partition `index` of `total`, as returned by
+    /// [`ShortCodeGenerator::into_partitioned_generators`], resuming after
+    /// the first `global_position` values of the unpartitioned sequence have
+    /// been used. This lets a worker join a running set of partitions, e.g.
+    /// when scaling out.
+    ///
+    /// `self` must be unpartitioned and have the same configuration as the
+    /// generator the other partitions were created from, and `base_position`
+    /// must be that generator's [`ShortCodeGenerator::position`] when it was
+    /// partitioned, since partitions are split relative to it.
+    pub fn join_partition(
+        mut self,
+        index: u32,
+        total: u32,
+        base_position: u64,
+        global_position: u64,
+    ) -> Result<Self, ParallelError> {
+        if self.skip.is_some() || self.block.is_some() {
+            return Err(ParallelError::AlreadyParallel);
+        }
+        if total == 0 {
+            return Err(ParallelError::ZeroPartitions);
+        }
+        if index >= total {
+            return Err(ParallelError::IndexOutOfRange { index, total });
+        }
+        if total as u64 > self.lcm.m {
+            return Err(ParallelError::ExceedsCapacity {
+                partitions: total,
+                capacity: self.lcm.m,
+            });
+        }
+        for position in [base_position, global_position] {
+            if position > self.lcm.m {
+                return Err(ParallelError::PositionOutOfRange {
+                    position,
+                    capacity: self.lcm.m,
+                });
+            }
+        }
+
+        // The partition owns the sequence indices congruent to
+        // `base_position + index`, so it resumes at the first of those at or
+        // after `global_position`.
+        let total_u64 = total as u64;
+        let residue = (base_position + index as u64) % total_u64;
+        let next_index =
+            global_position + (residue + total_u64 - global_position % total_u64) % total_u64;
+
+        self.seek(next_index.min(self.lcm.m));
+        // Stepping past the end of the cycle applies the exhaustion strategy,
+        // as the skip of a running partition would.
+        for _ in self.lcm.m..next_index {
+            self.step();
+        }
+
+        self.skip = Some(total - 1);
+        self.skip_before_next = false;
+        Ok(self)
+    }
+
+    /// Split this generator into `total` generators which each own a
+    /// contiguous run of the sequence, so that no two of them emit the same
+    /// code until they are exhausted.
+    ///
+    /// Unlike [`ShortCodeGenerator::into_partitioned_generators`], which
+    /// interleaves a single sequence, each partition here is exhausted once it
+    /// has emitted roughly `1 / total` of the code space. Under
+    /// [`ExhaustionStrategy::Cycle`] a partition restarts at the beginning of
+    /// its own run; under [`ExhaustionStrategy::IncreaseLength`] every partition
+    /// grows to the same new space (given the same rng state) and takes the
+    /// same share of it, so partitions remain disjoint.
+    ///
+    /// Partitions cover the whole cycle, so this should be called before any
+    /// codes are taken from `self`.
+    pub fn disjoint_partitions(&self, total: u32) -> Vec<Self> {
+        if self.skip.is_some() || self.block.is_some() {
+            panic!("Can't use disjoint_partitions on a generator that is already partitioned.");
+        }
+
+        (0..total)
+            .map(|index| {
+                let mut gen = self.clone();
+                let block = Block::partition(index, total, gen.lcm.m);
+                gen.lcm.seek(block.start);
+                gen.block = Some(block);
+                gen
+            })
+            .collect()
+    }
+
+    /// Reserve the next `count` values of the sequence for another issuer,
+    /// e.g. a downstream service, and advance this generator past them. The
+    /// other issuer creates a generator for the block with
+    /// [`ShortCodeGenerator::from_block`], so the two never issue the same
+    /// code and don't need to share state.
+    ///
+    /// The count includes values that are skipped by position constraints or
+    /// `IncreaseLengthPadded`, so the block may issue fewer than `count`
+    /// codes.
+    ///
+    /// Returns an error if the generator was created by
+    /// [`ShortCodeGenerator::into_partitioned_generators`], if `count` is
+    /// zero, or if fewer than `count` values remain in the current cycle. A
+    /// generator limited to a block (including one created by `from_block`)
+    /// can reserve blocks from its own block, which `from_block` accepts with
+    /// a copy of that generator as `config`.
+    pub fn reserve_block(&mut self, count: u64) -> Result<BlockReservation, ParallelError> {
+        if self.skip.is_some() {
+            return Err(ParallelError::AlreadyParallel);
+        }
+        if count == 0 {
+            return Err(ParallelError::EmptyBlock);
+        }
+
+        let remaining = self.remaining_values();
+        if count > remaining {
+            return Err(ParallelError::InsufficientCapacity {
+                requested: count,
+                remaining,
+            });
+        }
+
+        let start = self.position();
+        self.seek(start + count);
+
+        Ok(BlockReservation {
+            start,
+            count,
+            length: self.length as usize,
+        })
+    }
+
+    /// Create a generator limited to a block reserved with
+    /// [`ShortCodeGenerator::reserve_block`]. `config` must have the same
+    /// configuration (alphabet, rng and length) as the generator the block
+    /// was reserved from, e.g. a copy of it, or one built from the same seed.
+    ///
+    /// The generator issues the codes of the block, and is then exhausted:
+    /// it repeats the block under [`ExhaustionStrategy::Cycle`], and panics
+    /// otherwise, since a block can't grow to a longer length.
+    ///
+    /// If `config` is itself limited to a block, the reservation must lie
+    /// within that block, and replaces it.
+    ///
+    /// Panics if `config` has a different length from the reservation, is
+    /// partitioned by [`ShortCodeGenerator::into_partitioned_generators`], or
+    /// is too short for the block.
+    pub fn from_block(config: &Self, reservation: BlockReservation) -> Self {
+        assert_eq!(
+            config.length as usize, reservation.length,
+            "Block was reserved at length {}, but the generator has length {}.",
+            reservation.length, config.length
+        );
+        assert!(
+            config.skip.is_none(),
+            "Can't create a block from a partitioned generator."
+        );
+        assert!(
+            reservation.start + reservation.count <= config.lcm.m,
+            "Block is past the end of the code space ({}).",
+            config.lcm.m
+        );
+        if let Some(block) = &config.block {
+            assert!(
+                reservation.start >= block.start
+                    && reservation.start + reservation.count <= block.start + block.len,
+                "Block is outside of the generator's block."
+            );
+        }
+
+        let mut gen = config.clone();
+        gen.block = Some(Block {
+            start: reservation.start,
+            len: reservation.count,
+            used: 0,
+            partition: None,
+        });
+        gen.lcm.seek(reservation.start);
+        gen.skip_before_next = false;
+        gen.growth_count = 0;
+        gen.cycle_count = 0;
+        gen.generated_count = 0;
+        if !matches!(gen.effective_strategy(), ExhaustionStrategy::Cycle) {
+            gen.exhaustion_strategy = StrategySetting {
+                strategy: ExhaustionStrategy::Panic,
+                unrecognized: false,
+            };
+        }
+        gen
+    }
+
+    /// Check that an alphabet of `alphabet_len` symbols and codes of `length`
+    /// symbols make a usable configuration, without building a generator or
+    /// requiring entropy, e.g. to reject bad configuration at startup.
+    ///
+    /// Returns the error the checked constructors would return, and also
+    /// rejects a single-symbol alphabet, which can only form one code of each
+    /// length.
+    ///
+    /// ```
+    /// use tiny_id::{AlphabetError, ShortCodeGenerator};
+    ///
+    /// assert!(ShortCodeGenerator::<char>::validate_config(36, 5).is_ok());
+    /// assert_eq!(
+    ///     Err(AlphabetError::SingleSymbol),
+    ///     ShortCodeGenerator::<char>::validate_config(1, 5)
+    /// );
+    /// ```
+    pub fn validate_config(alphabet_len: usize, length: usize) -> Result<(), AlphabetError> {
+        checked_capacity(alphabet_len, length)?;
+
+        if alphabet_len == 1 {
+            return Err(AlphabetError::SingleSymbol);
+        }
+
+        Ok(())
+    }
+
+    /// Create a short code generator from a typed alphabet and length, using
+    /// the given ChaCha12Rng random number generator. Equivalent to
+    /// [`ShortCodeGenerator::try_with_alphabet_and_rng`], but the arguments
+    /// can't be swapped by mistake.
+    ///
+    /// ```
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use tiny_id::{Alphabet, Length, ShortCodeGenerator};
+    ///
+    /// let rng = rand_chacha::ChaCha12Rng::seed_from_u64(1);
+    /// let mut generator =
+    ///     ShortCodeGenerator::build(Alphabet("abc".chars().collect()), Length(4), rng).unwrap();
+    /// assert_eq!(4, generator.next_string().len());
+    /// ```
+    pub fn build(
+        alphabet: Alphabet<T>,
+        length: Length,
+        rng: ChaCha12Rng,
+    ) -> Result<Self, AlphabetError> {
+        Self::try_with_alphabet_and_rng(alphabet.0, length.0, rng)
+    }
+
+    /// Create a short code generator using a given alphabet, using the given
+    /// ChaCha12Rng random number generator. Prefer
+    /// [`ShortCodeGenerator::build`], which is harder to call incorrectly.
+    ///
+    /// Panics if the alphabet is empty or the number of possible codes
+    /// overflows a `u64`. See [`ShortCodeGenerator::try_with_alphabet_and_rng`]
+    /// for a non-panicking version.
+    pub fn with_alphabet_and_rng(alphabet: Vec<T>, length: usize, rng: ChaCha12Rng) -> Self {
+        Self::try_with_alphabet_and_rng(alphabet, length, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Create a short code generator using a given alphabet, using the given
+    /// ChaCha12Rng random number generator.
+    ///
+    /// Returns an error if the alphabet is empty or the number of possible
+    /// codes overflows a `u64`.
+    pub fn try_with_alphabet_and_rng(
+        alphabet: Vec<T>,
+        length: usize,
+        mut rng: ChaCha12Rng,
+    ) -> Result<Self, AlphabetError> {
+        let m = checked_capacity(alphabet.len(), length)?;
+        let (lcm, offset) = seed_lcm(alphabet.len() as u32, m, 1, &mut rng);
+        if !lcm.has_full_period() {
+            return Err(AlphabetError::UnsupportedSize {
+                alphabet_len: alphabet.len(),
+            });
+        }
+
+        Ok(Self::from_sequence(
+            alphabet,
+            length,
+            lcm,
+            offset,
+            Some(rng),
+        ))
+    }
+
+    /// Create a generator over the given sequence, with every option at its
+    /// default.
+    fn from_sequence(
+        alphabet: Vec<T>,
+        length: usize,
+        lcm: LinearCongruentMultiplier,
+        offset: u64,
+        rng: Option<ChaCha12Rng>,
+    ) -> Self {
+        Self {
+            alphabet: alphabet.into(),
+            lcm,
+            offset,
+            length: length as u32,
+            exhaustion_strategy: StrategySetting::default(),
+            rng,
+            skip: None,
+            skip_before_next: false,
+            block: None,
+            growth_count: 0,
+            cycle_count: 0,
+            max_length: None,
+            constraints: Vec::new(),
+            past_sequences: Vec::new(),
+            epoch: None,
+            increment: None,
+            check_symbol: false,
+            signature: None,
+            display_width: None,
+            whitening: false,
+            integer_source: IntegerSource::LcmScrambled,
+            partition_marker: None,
+            generated_count: 0,
+            warning_hook: None,
+            format_version: FORMAT_VERSION,
+        }
+    }
+
+    /// Create a generator from a schema returned by
+    /// [`ShortCodeGenerator::schema`], at position `cursor` of its sequence
+    /// (see [`ShortCodeGenerator::position`]). Generators restored from the
+    /// same schema generate the same codes at the same positions.
+    ///
+    /// The restored generator has the default exhaustion strategy and none
+    /// of the options that aren't part of the schema, like partitioning or
+    /// check symbols. It has no rng, so if it increases its length, the
+    /// longer sequence is seeded from entropy (or, without the `getrandom`
+    /// feature, the generator panics) and differs between services.
+    ///
+    /// Returns an error if the schema doesn't describe a valid sequence, e.g.
+    /// because it was edited by hand. Panics if `cursor` is greater than the
+    /// number of codes.
+    pub fn restore(schema: CodeSchema<T>, cursor: u64) -> Result<Self, AlphabetError> {
+        let m = checked_capacity(schema.alphabet.len(), schema.length)?;
+        let lcm = LinearCongruentMultiplier::new(schema.first, schema.m, schema.c, schema.a);
+        if schema.m != m || schema.offset >= m || !lcm.has_full_period() {
+            return Err(AlphabetError::UnsupportedSize {
+                alphabet_len: schema.alphabet.len(),
+            });
+        }
+
+        let mut generator =
+            Self::from_sequence(schema.alphabet, schema.length, lcm, schema.offset, None);
+        generator.increment = (schema.c != 1).then_some(schema.c);
+        generator.whitening = schema.whitening;
+        generator.integer_source = schema.integer_source;
+        generator.seek(cursor);
+        Ok(generator)
+    }
+
+    /// Create a short code generator using a given alphabet, drawing the
+    /// starting point of the sequence from `seed_rng` and the offset added to
+    /// every value from `offset_rng`.
+    ///
+    /// [`ShortCodeGenerator::with_alphabet_and_rng`] draws both from a single
+    /// rng. Using two independently seeded rngs means that an attacker who
+    /// recovers one seed still can't reconstruct the sequence of codes. Only
+    /// `seed_rng` is kept in the generator, so the sequence chosen after a
+    /// length increase is drawn entirely from it.
+    ///
+    /// Panics in the same cases as [`ShortCodeGenerator::with_alphabet_and_rng`].
+    pub fn with_alphabet_and_rngs(
+        alphabet: Vec<T>,
+        length: usize,
+        seed_rng: ChaCha12Rng,
+        mut offset_rng: ChaCha12Rng,
+    ) -> Self {
+        let mut gen = Self::with_alphabet_and_rng(alphabet, length, seed_rng);
+        gen.offset = offset_rng.gen_range(0..gen.lcm.m);
+        gen
+    }
+
+    /// Create a short code generator using a given alphabet, seeding its
+    /// ChaCha12Rng random number generator with `seed`.
+    ///
+    /// The same seed, alphabet, length and configuration always produce the
+    /// same sequence of codes with a given version of this crate. Panics in
+    /// the same cases as [`ShortCodeGenerator::with_alphabet_and_rng`].
+    pub fn with_alphabet_and_seed_bytes(alphabet: Vec<T>, length: usize, seed: [u8; 32]) -> Self {
+        Self::with_alphabet_and_rng(alphabet, length, ChaCha12Rng::from_seed(seed))
+    }
+
+    /// Create a short code generator using a given alphabet, seeded with a
+    /// fixed constant, so that every call produces the same sequence of
+    /// codes. For tests, including tests of code that uses this crate.
+    ///
+    /// **Not for production use:** anyone can reproduce the codes of a
+    /// generator created this way. Panics in the same cases as
+    /// [`ShortCodeGenerator::with_alphabet_and_rng`].
+    pub fn with_alphabet_for_test(alphabet: Vec<T>, length: usize) -> Self {
+        Self::with_alphabet_and_seed_bytes(alphabet, length, [0; 32])
+    }
+
+    /// Create a short code generator using a given alphabet, choosing the
+    /// shortest length that provides at least `min_capacity` codes (see
+    /// [`min_length_for`]).
+    pub fn for_capacity(
+        alphabet: Vec<T>,
+        min_capacity: u64,
+        rng: ChaCha12Rng,
+    ) -> Result<Self, AlphabetError> {
+        let length = min_length_for(alphabet.len(), min_capacity)?;
+        Self::try_with_alphabet_and_rng(alphabet, length, rng)
+    }
+
+    /// Create a short code generator using a given alphabet.
+    ///
+    /// Returns an error if the alphabet is empty or the number of possible
+    /// codes overflows a `u64`.
+    #[cfg(feature = "getrandom")]
+    pub fn try_with_alphabet(alphabet: Vec<T>, length: usize) -> Result<Self, AlphabetError> {
+        let mut seed: [u8; 32] = Default::default();
+        getrandom::getrandom(&mut seed).expect("Error getting entropy.");
+        let rng = ChaCha12Rng::from_seed(seed);
+        Self::try_with_alphabet_and_rng(alphabet, length, rng)
+    }
+
+    /// Create a short code generator using a given alphabet.
+    #[cfg(feature = "getrandom")]
+    pub fn with_alphabet(alphabet: Vec<T>, length: usize) -> Self {
+        let mut seed: [u8; 32] = Default::default();
+        getrandom::getrandom(&mut seed).expect("Error getting entropy.");
+        let rng = ChaCha12Rng::from_seed(seed);
+        Self::with_alphabet_and_rng(alphabet, length, rng)
+    }
+
+    fn exhausted(&self) -> bool {
+        match &self.block {
+            Some(block) => block.used >= block.len,
+            None => self.lcm.exhausted(),
+        }
+    }
+
+    /// Replace the LCM with a new one for codes one symbol longer. Other
+    /// configuration is preserved.
+    fn increase_length(&mut self) {
+        if matches!(
+            self.exhaustion_strategy.strategy,
+            ExhaustionStrategy::IncreaseLengthPadded
+        ) && self.alphabet.len() < 2
+        {
+            panic!("IncreaseLengthPadded requires an alphabet of at least two symbols.");
+        }
+
+        let m = checked_capacity(self.alphabet.len(), self.length as usize + 1)
+            .unwrap_or_else(|err| panic!("Can't increase code length: {}", err));
+        self.past_sequences.push(PastSequence {
+            length: self.length,
+            lcm: self.lcm.clone(),
+            offset: self.offset,
+        });
+        self.reseed(m);
+        self.length += 1;
+        self.growth_count += 1;
+        self.format_version = FORMAT_VERSION;
+    }
+
+    /// Replace the LCM with a new one with modulus `m`, drawn from the rng
+    /// (or counting up from zero, for unscrambled integer sources), and
+    /// recompute the block to match.
+    fn reseed(&mut self, m: u64) {
+        let c = self.increment.unwrap_or(1);
+        if self.integer_source == IntegerSource::LcmScrambled {
+            let mut rng = self.rng_or_entropy();
+            let (lcm, offset) = seed_lcm(self.alphabet.len() as u32, m, c, &mut rng);
+
+            self.lcm = lcm;
+            self.offset = offset;
+            self.rng = Some(rng);
+        } else {
+            self.lcm = LinearCongruentMultiplier::new(0, m, c, 1);
+            self.offset = 0;
+        }
+
+        if let Some(block) = &mut self.block {
+            let (index, total) = block
+                .partition
+                .expect("Can't increase the length of a generator limited to a block.");
+            *block = Block::partition(index, total, self.lcm.m);
+            self.lcm.seek(block.start);
+        }
+    }
+
+    /// Return a copy of the rng, or a new one seeded from entropy if the
+    /// generator was deserialized from state without one.
+    fn rng_or_entropy(&self) -> ChaCha12Rng {
+        if let Some(rng) = self.rng.clone() {
+            rng
+        } else {
+            #[cfg(feature = "getrandom")]
+            {
+                let mut seed: [u8; 32] = Default::default();
+                getrandom::getrandom(&mut seed).expect("Error getting entropy.");
+                ChaCha12Rng::from_seed(seed)
+            }
+
+            #[cfg(not(feature = "getrandom"))]
+            {
+                if matches!(
+                    self.exhaustion_strategy.strategy,
+                    ExhaustionStrategy::IncreaseLengthDeterministic
+                ) {
+                    return self.derived_rng();
+                }
+
+                panic!("Need crate feature getrandom to increase the length of a pre-0.1.4 ShortCodeGenerator. See https://github.com/paulgb/tiny_id/issues/2")
+            }
+        }
+    }
+
+    /// Return an rng seeded from the current sequence, for
+    /// `ExhaustionStrategy::IncreaseLengthDeterministic`.
+    #[cfg(not(feature = "getrandom"))]
+    fn derived_rng(&self) -> ChaCha12Rng {
+        let (first, a, c) = self.lcm.params();
+        let mut seed = [0; 32];
+        for (chunk, word) in seed.chunks_mut(8).zip([first, a, c, self.offset]) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        ChaCha12Rng::from_seed(seed)
+    }
+
+    /// Create a generator with the same alphabet, length and options as this
+    /// one, but an independent sequence drawn from this generator's rng.
+    ///
+    /// Unlike [`ShortCodeGenerator::into_partitioned_generators`], forks are
+    /// not coordinated: each covers the whole code space in its own order,
+    /// so codes from different forks can collide. The fork starts at the
+    /// beginning of its sequence, unpartitioned, with no earlier sequences and
+    /// its epoch (if any) at zero. Forking advances this generator's rng, so
+    /// successive forks differ; codes at the current length are unaffected,
+    /// but the sequence after a length increase is.
+    ///
+    /// Panics if the generator uses an [`IntegerSource`] other than
+    /// `LcmScrambled`, since the other sources don't use the rng and a fork
+    /// would repeat this generator's sequence. A generator deserialized from
+    /// pre-0.1.4 state has no rng, so forking it requires the `getrandom`
+    /// feature.
+    pub fn fork(&mut self) -> Self {
+        assert!(
+            self.integer_source == IntegerSource::LcmScrambled,
+            "Can't fork a generator with integer source {:?}.",
+            self.integer_source
+        );
+
+        let mut rng = self.rng_or_entropy();
+        let seed: [u8; 32] = rng.gen();
+        self.rng = Some(rng);
+
+        let mut sibling = self.clone();
+        sibling.rng = Some(ChaCha12Rng::from_seed(seed));
+        sibling.skip = None;
+        sibling.skip_before_next = false;
+        sibling.block = None;
+        sibling.growth_count = 0;
+        sibling.cycle_count = 0;
+        sibling.generated_count = 0;
+        sibling.past_sequences = Vec::new();
+        if let Some(epoch) = &mut sibling.epoch {
+            epoch.value = 0;
+        }
+        sibling.reseed(self.lcm.m);
+        sibling
+    }
+
+    /// Return the exhaustion strategy to apply now, taking the length cap into
+    /// account.
+    fn effective_strategy(&self) -> ExhaustionStrategy {
+        match (self.exhaustion_strategy.strategy, self.max_length) {
+            (
+                ExhaustionStrategy::IncreaseLength
+                | ExhaustionStrategy::IncreaseLengthPadded
+                | ExhaustionStrategy::IncreaseLengthDeterministic,
+                Some(max_length),
+            ) if self.length >= max_length.length => match max_length.fallback {
+                ExhaustionStrategy::Cycle => ExhaustionStrategy::Cycle,
+                _ => ExhaustionStrategy::Panic,
+            },
+            (strategy, _) => strategy,
+        }
+    }
+
+    fn step(&mut self) -> u64 {
+        if self.exhausted() {
+            match self.effective_strategy() {
+                ExhaustionStrategy::Cycle => {
+                    if let Some(block) = &mut self.block {
+                        block.used = 0;
+                        self.lcm.seek(block.start);
+                    }
+                    self.lcm.restart_cycle();
+                    self.cycle_count += 1;
+                }
+                ExhaustionStrategy::Panic | ExhaustionStrategy::WarnThenPanic => panic!(
+                    "ShortCodeGenerator exhausted: base={} length={} capacity={}",
+                    self.alphabet.len(),
+                    self.length,
+                    self.lcm.m
+                ),
+                ExhaustionStrategy::IncreaseLength
+                | ExhaustionStrategy::IncreaseLengthPadded
+                | ExhaustionStrategy::IncreaseLengthDeterministic => self.increase_length(),
+            }
+        }
+
+        if let Some(block) = &mut self.block {
+            block.used += 1;
+        }
+        self.lcm.next()
+    }
+
+    /// Return the next short code, represented as an integer.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_int(&mut self) -> u64 {
+        self.next_value(false)
+            .expect("Only a scan within the cycle runs out of values.")
+    }
+
+    /// Shared implementation of [`ShortCodeGenerator::next_int`]. If
+    /// `within_cycle` is set, returns `None` instead of applying the
+    /// exhaustion strategy when the rest of the current cycle has no value
+    /// to emit, e.g. because the position constraints reject them all.
+    fn next_value(&mut self, within_cycle: bool) -> Option<u64> {
+        let mut rejected = 0;
+        let warn = self.warning_hook.filter(|_| {
+            matches!(self.effective_strategy(), ExhaustionStrategy::WarnThenPanic)
+                && !self.is_near_exhaustion(WARNING_THRESHOLD)
+        });
+
+        loop {
+            if within_cycle && self.cycle_exhausted() {
+                return None;
+            }
+
+            if self.skip_before_next {
+                // Each skipped value goes through `step`, so a skip that crosses
+                // the end of the cycle triggers the exhaustion strategy exactly
+                // as it would for the unpartitioned generator.
+                for _ in 0..self.skip.unwrap_or_default() {
+                    self.step();
+                }
+            } else {
+                self.skip_before_next = true;
+            }
+
+            // Rejected values are skipped along with the rest of the stride, so
+            // that partitions stay disjoint.
+            let step = self.step();
+            let result = self.output_value(step);
+            if self.reserved_for_padding(result) {
+                continue;
+            }
+            if !self.satisfies_constraints(result) {
+                rejected += 1;
+                assert!(
+                    rejected < MAX_CONSTRAINT_REJECTIONS,
+                    "Position constraints rejected {} codes in a row.",
+                    rejected
+                );
+                continue;
+            }
+
+            self.generated_count += 1;
+            if let Some(hook) = warn {
+                if self.is_near_exhaustion(WARNING_THRESHOLD) {
+                    hook(self.remaining());
+                }
+            }
+            return Some(result);
+        }
+    }
+
+    /// Map a value of the underlying sequence to the value that is rendered
+    /// as a code, by adding the offset and applying the integer source and
+    /// any output whitening.
+    fn output_value(&self, lcm_value: u64) -> u64 {
+        let mut value = add_mod(lcm_value, self.offset, self.lcm.m);
+        if self.integer_source == IntegerSource::Halton {
+            value = reverse_digits(value, self.alphabet.len() as u64, self.length);
+        }
+        if self.whitening {
+            whitening::whiten(value, self.alphabet.len() as u64, self.length)
+        } else {
+            value
+        }
+    }
+
+    /// Returns `true` if leading symbols are banned with
+    /// [`ShortCodeGenerator::with_banned_leading`].
+    fn has_banned_leading(&self) -> bool {
+        self.constraints.iter().any(|constraint| constraint.leading)
+    }
+
+    /// Returns `true` if `value` renders to a code that satisfies the
+    /// constraints set by [`ShortCodeGenerator::with_position_constraint`].
+    fn satisfies_constraints(&self, value: u64) -> bool {
+        let alphabet_size = self.alphabet.len() as u64;
+
+        self.constraints.iter().all(|constraint| {
+            let digit = alphabet_size
+                .checked_pow(constraint.position)
+                .map_or(0, |place| value / place % alphabet_size);
+            constraint.allowed[digit as usize]
+        })
+    }
+
+    /// Like [`ShortCodeGenerator::next_int`], but also returns `true` if the
+    /// sequence wrapped around under [`ExhaustionStrategy::Cycle`] during
+    /// this call, e.g. to advance an external epoch counter.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_int_checked(&mut self) -> (u64, bool) {
+        let cycle_count = self.cycle_count;
+        let value = self.next_int();
+        (value, self.cycle_count != cycle_count)
+    }
+
+    /// Return the next unique value as an integer, without rendering it with
+    /// the alphabet. Values are scrambled (not sequential) and lie in
+    /// `[0, m)`, where `m` is [`ShortCodeGenerator::capacity`] at the time
+    /// the value is generated.
+    ///
+    /// This is the same value as [`ShortCodeGenerator::next_int`]; all
+    /// `next_*` calls are equivalent in terms of the resulting state of self.
+    pub fn next_u64(&mut self) -> u64 {
+        self.next_int()
+    }
+
+    /// Return [`ShortCodeGenerator::next_u64`] as big-endian bytes, for use
+    /// in binary protocols.
+    pub fn next_be_bytes(&mut self) -> [u8; 8] {
+        self.next_u64().to_be_bytes()
+    }
+
+    /// Returns `true` if `value` renders to a code starting with the first
+    /// symbol of the alphabet and is therefore reserved for padded codes from
+    /// before a length increase (see `ExhaustionStrategy::IncreaseLengthPadded`).
+    fn reserved_for_padding(&self, value: u64) -> bool {
+        matches!(
+            self.exhaustion_strategy.strategy,
+            ExhaustionStrategy::IncreaseLengthPadded
+        ) && self.growth_count > 0
+            && value.is_multiple_of(self.alphabet.len() as u64)
+    }
+
+    /// Return the first code of the current cycle. Under
+    /// [`ExhaustionStrategy::Cycle`], this is the code that is generated again
+    /// when the cycle wraps around.
+    pub fn cycle_start(&self) -> Vec<T> {
+        self.code_at(0)
+    }
+
+    /// Return the code generated at `index` in the current cycle, i.e. when
+    /// [`ShortCodeGenerator::position`] is `index`. This is the inverse of
+    /// [`ShortCodeGenerator::decode_vec`].
+    ///
+    /// Panics if `index` is not less than [`ShortCodeGenerator::capacity`].
+    pub fn code_at(&self, index: u64) -> Vec<T> {
+        assert!(
+            index < self.lcm.m,
+            "Index {} is past the end of the code space ({}).",
+            index,
+            self.lcm.m
+        );
+
+        let value = self.output_value(self.lcm.value_at(index));
+        render(value, &self.alphabet, self.length)
+    }
+
+    /// Assign a code to each of `keys`, such that the key with the `i`th
+    /// smallest value gets [`ShortCodeGenerator::code_at`]`(i)`. Returns the
+    /// code of each key, in the same order as `keys`. Keys with the same value
+    /// get consecutive codes, in the order they appear in `keys`.
+    ///
+    /// This is for assigning codes to existing rows in bulk, reproducibly:
+    /// the same keys always get the same codes. It needs all the keys up
+    /// front, and doesn't advance the generator, so the codes it assigns
+    /// should not also be issued by `next_*` (e.g. by
+    /// [`ShortCodeGenerator::seek`]ing past them).
+    ///
+    /// Panics if there are more keys than [`ShortCodeGenerator::capacity`].
+    pub fn assign_sorted(&self, keys: &[u64]) -> Vec<Vec<T>> {
+        assert!(
+            keys.len() as u64 <= self.lcm.m,
+            "Can't assign {} codes from a space of {}.",
+            keys.len(),
+            self.lcm.m
+        );
+
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by_key(|&i| keys[i]);
+
+        let mut codes = vec![Vec::new(); keys.len()];
+        for (rank, i) in order.into_iter().enumerate() {
+            codes[i] = self.code_at(rank as u64);
+        }
+        codes
+    }
+
+    /// Return an iterator of codes paired with the index in the current cycle
+    /// at which each was generated (see [`ShortCodeGenerator::code_at`]).
+    ///
+    /// Each item is equivalent to a `next_vec` call, so the iterator applies
+    /// the exhaustion strategy and never ends on its own. If the code length
+    /// increases, indices restart from the beginning of the new cycle.
+    pub fn enumerate_codes(&mut self) -> impl Iterator<Item = (u64, Vec<T>)> + '_ {
+        std::iter::repeat_with(move || {
+            let (code, index) = self.next_with_index();
+            (index, code)
+        })
+    }
+
+    /// Undo the most recent `next_*` call, returning its code to the pool so
+    /// that the following `next_*` call produces it again.
+    ///
+    /// Only the most recent code can be returned. Returns `false` (and does
+    /// nothing) if no code has been generated yet, or if the most recent code
+    /// has already been returned.
+    pub fn unget(&mut self) -> bool {
+        if !self.skip_before_next {
+            return false;
+        }
+
+        self.lcm.prev();
+        if let Some(block) = &mut self.block {
+            block.used -= 1;
+        }
+
+        // Any values skipped before the returned code stay skipped.
+        self.skip_before_next = false;
+        self.generated_count = self.generated_count.saturating_sub(1);
+        true
+    }
+
+    /// Returns `true` if this generator has generated any codes, not counting
+    /// a code returned by [`ShortCodeGenerator::unget`]. This is preserved by
+    /// serialization.
+    pub fn has_generated(&self) -> bool {
+        if self.skip_before_next || self.growth_count > 0 || self.cycle_count > 0 {
+            return true;
+        }
+
+        match &self.block {
+            Some(block) => block.used > 0,
+            // A fresh interleaved partition starts up to `skip` values into the
+            // sequence.
+            None => self.position() > self.skip.unwrap_or_default() as u64,
+        }
+    }
+
+    /// Return the number of codes this generator has emitted, across all
+    /// cycles and lengths. Codes returned by [`ShortCodeGenerator::unget`] or
+    /// [`ShortCodeGenerator::rev`] are subtracted; [`ShortCodeGenerator::seek`]
+    /// doesn't change the count. For partitioned generators, this counts only
+    /// this partition's codes.
+    pub fn generated_count(&self) -> u64 {
+        self.generated_count
+    }
+
+    /// Return the number of distinct codes this generator has emitted, i.e.
+    /// [`ShortCodeGenerator::generated_count`] capped at this generator's
+    /// share of the code space at the current length. Under
+    /// [`ExhaustionStrategy::Cycle`], `generated_count` keeps climbing after
+    /// the sequence wraps around, but repeated codes aren't counted here.
+    pub fn distinct_issued(&self) -> u64 {
+        let (_, share) = self.partition_usage();
+        self.generated_count.min(share)
+    }
+
+    /// Return the number of times the code length has been increased since
+    /// the generator was created.
+    pub fn growth_count(&self) -> u32 {
+        self.growth_count
+    }
+
+    /// Returns `true` if the generator's sequence covers every code of the
+    /// current length before repeating. This always holds for generators
+    /// built by this crate, but can be used to check deserialized state.
+    pub fn verify_full_period(&self) -> bool {
+        self.lcm.has_full_period()
+    }
+
+    /// Return the number of symbols in a generated code, including any epoch
+    /// prefix, check symbol and signature.
+    fn full_length(&self) -> usize {
+        self.prefix_length()
+            + self.length as usize
+            + self.check_symbol as usize
+            + self.signature_length()
+    }
+
+    /// Return the number of signature symbols at the end of each code.
+    fn signature_length(&self) -> usize {
+        self.signature
+            .as_ref()
+            .map_or(0, |sig| sig.symbols as usize)
+    }
+
+    /// Return the number of symbols in front of the code proper: the
+    /// partition marker, epoch and display padding, if set.
+    fn prefix_length(&self) -> usize {
+        self.partition_marker.is_some() as usize
+            + self.epoch.map_or(0, |epoch| epoch.symbols as usize)
+            + self.padding_length()
+    }
+
+    /// Return the number of symbols of padding added in front of the code
+    /// proper to reach the display width.
+    fn padding_length(&self) -> usize {
+        self.display_width
+            .map_or(0, |width| width.saturating_sub(self.length) as usize)
+    }
+
+    /// Returns `true` if codes of `code_len` symbols are generated at the
+    /// current length, counting any epoch prefix and check symbol.
+    ///
+    /// After a length increase, codes issued at the old length are no longer
+    /// accepted: decoding them against the new code space would give a
+    /// meaningless index, so [`ShortCodeGenerator::decode_vec`] and
+    /// [`ShortCodeGenerator::validate_full`] reject them as well.
+    pub fn accepts_length(&self, code_len: usize) -> bool {
+        code_len == self.full_length()
+    }
+
+    /// Return how many values the underlying sequence produces before
+    /// repeating at the current length. This is
+    /// [`ShortCodeGenerator::capacity`] for every generator built by this
+    /// crate; a smaller value means that some codes are never generated (see
+    /// [`ShortCodeGenerator::verify_full_period`]).
+    ///
+    /// This is computed from the sequence's parameters without walking it.
+    pub fn period(&self) -> u64 {
+        self.lcm.period()
+    }
+
+    /// Returns `true` if `value` is in the current code space, i.e. less than
+    /// [`ShortCodeGenerator::capacity`], so that
+    /// [`ShortCodeGenerator::encode_int`] can render it.
+    pub fn in_space(&self, value: u64) -> bool {
+        value < self.lcm.m
+    }
+
+    /// Render `value` as a code, in the same way as `next_vec` renders the
+    /// value returned by [`ShortCodeGenerator::next_int`] (including any epoch
+    /// prefix and check symbol). This doesn't advance the generator or check
+    /// whether `value` has been generated.
+    ///
+    /// Returns `None` if `value` is not [in the code space](ShortCodeGenerator::in_space).
+    pub fn encode_int(&self, value: u64) -> Option<Vec<T>> {
+        self.in_space(value).then(|| self.render_code(value))
+    }
+
+    /// Return a distribution that samples uniformly random codes of this
+    /// generator's alphabet and current length, for use with the `rand`
+    /// crate. Samples can repeat, unlike the codes this generator issues; see
+    /// [`CodeDistribution`]. Epoch prefixes, check symbols and partition
+    /// markers are not included.
+    pub fn distribution(&self) -> CodeDistribution<T> {
+        CodeDistribution::new(self.alphabet.clone(), self.length, self.lcm.m)
+    }
+
+    /// Return an estimate, in bytes, of the size of this generator when
+    /// serialized with a compact binary format such as `bincode`. Formats
+    /// like JSON take several times more.
+    ///
+    /// The estimate is dominated by the alphabet, counted as
+    /// `size_of::<T>()` bytes per symbol plus a length prefix (an upper bound
+    /// for `char`; strings are counted by their pointer size, not their
+    /// contents), and by the rng, which takes 56 bytes. Position
+    /// constraints add a byte per symbol of the alphabet each, each length
+    /// increase about 50 bytes. Everything
+    /// else takes about 150 bytes. When storing many generators, a
+    /// [`ShortCodeGenerator::cursor_token`] for each one along with a single
+    /// shared configuration takes much less space.
+    pub fn serialized_size_hint(&self) -> usize {
+        const LEN_PREFIX: usize = 8;
+        const OPTION_TAG: usize = 1;
+
+        // `first`, `next`, `m`, `c`, `a` and `exhausted`.
+        let lcm = 5 * 8 + 1;
+        // Seed, stream and word position.
+        let rng = OPTION_TAG + 32 + 8 + 16;
+        let alphabet = LEN_PREFIX + self.alphabet.len() * std::mem::size_of::<T>();
+        let constraints = LEN_PREFIX
+            + self
+                .constraints
+                .iter()
+                .map(|constraint| 4 + LEN_PREFIX + constraint.allowed.len() + 1)
+                .sum::<usize>();
+        let past_sequences = LEN_PREFIX + self.past_sequences.len() * (4 + lcm + 8);
+        let signature = OPTION_TAG + self.signature.as_ref().map_or(0, |_| 4);
+
+        let other = 8 // offset
+            + 4 // length
+            + 4 // exhaustion_strategy
+            + OPTION_TAG + 4 // skip
+            + 1 // skip_before_next
+            + OPTION_TAG + 3 * 8 // block
+            + 4 // growth_count
+            + 8 // cycle_count
+            + OPTION_TAG + 4 + 4 // max_length
+            + OPTION_TAG + 4 + 8 // epoch
+            + OPTION_TAG + 8 // increment
+            + 8 // generated_count
+            + OPTION_TAG + 4 // partition_marker
+            + OPTION_TAG + 4 // display_width
+            + 1 // whitening
+            + 4 // integer_source
+            + 1 // format_version
+            + 1; // check_symbol
+
+        lcm + rng + alphabet + constraints + past_sequences + signature + other
+    }
+
+    /// Return everything that determines this generator's sequence of codes
+    /// at the current length, without its position, for sharing with
+    /// generators that keep their own cursors; see
+    /// [`ShortCodeGenerator::restore`].
+    pub fn schema(&self) -> CodeSchema<T> {
+        let (first, a, c) = self.lcm.params();
+        CodeSchema {
+            alphabet: self.alphabet.to_vec(),
+            length: self.length as usize,
+            first,
+            a,
+            c,
+            m: self.lcm.m,
+            offset: self.offset,
+            whitening: self.whitening,
+            integer_source: self.integer_source,
+        }
+    }
+
+    /// Return the number of possible codes at the current length, i.e. the
+    /// alphabet size to the power of the length.
+    pub fn capacity(&self) -> u64 {
+        self.lcm.m
+    }
+
+    /// Return how many codes this generator emits between two emissions of
+    /// the same code, or `None` if codes never recur.
+    ///
+    /// Codes only recur under [`ExhaustionStrategy::Cycle`] (including as the
+    /// fallback of [`ShortCodeGenerator::with_max_length`] once the length
+    /// cap is reached). The interval is [`ShortCodeGenerator::capacity`] for
+    /// an unpartitioned generator, and this partition's share of it for a
+    /// partitioned one. Codes skipped by position constraints are not
+    /// counted.
+    pub fn recurrence_interval(&self) -> Option<u64> {
+        if !matches!(self.effective_strategy(), ExhaustionStrategy::Cycle) {
+            return None;
+        }
+
+        let m = self.lcm.m;
+        let interval = match (&self.block, self.skip) {
+            (Some(block), _) => block.len,
+            // The partition emits every `stride`th value of the sequence, so it
+            // returns to the same value after `m / gcd(m, stride)` emissions.
+            (None, Some(skip)) => {
+                let stride = skip as u64 + 1;
+                let (mut a, mut b) = (m, stride);
+                while b != 0 {
+                    (a, b) = (b, a % b);
+                }
+                m / a
+            }
+            (None, None) => m,
+        };
+
+        Some(interval)
+    }
+
+    /// Return the number of bits of entropy in a code of the current length,
+    /// i.e. `log2` of [`ShortCodeGenerator::capacity`].
+    ///
+    /// This measures the size of the code space, not how hard codes are to
+    /// predict: anyone who learns the generator's state (including the rng
+    /// seed and offset) can predict every code, and consecutive codes are
+    /// related by a simple formula.
+    pub fn entropy_bits(&self) -> f64 {
+        self.length as f64 * (self.alphabet.len() as f64).log2()
+    }
+
+    /// Return the probability that a single guess of a code of the current
+    /// length matches a particular code, i.e. `1 / capacity()`. Subject to
+    /// the same caveat as [`ShortCodeGenerator::entropy_bits`].
+    pub fn guess_probability(&self) -> f64 {
+        1.0 / self.lcm.m as f64
+    }
+
+    /// Return the number of values of the current cycle that have been used
+    /// up, between `0` and [`ShortCodeGenerator::capacity`].
+    ///
+    /// For partitioned generators this counts values used by all partitions
+    /// sharing the sequence, up to the most recent one taken by this partition.
+    pub fn position(&self) -> u64 {
+        let index = self.lcm.index();
+        if index == 0 && self.exhausted() {
+            self.lcm.m
+        } else {
+            index
+        }
+    }
+
+    /// Move the generator to the given position in the current cycle, so that
+    /// [`ShortCodeGenerator::position`] returns `position`. Together with
+    /// `position`, this can be used to resume a generator reconstructed from
+    /// the same configuration and rng.
+    ///
+    /// Panics if `position` is greater than [`ShortCodeGenerator::capacity`],
+    /// or outside the block of a generator created by
+    /// [`ShortCodeGenerator::disjoint_partitions`].
+    pub fn seek(&mut self, position: u64) {
+        assert!(
+            position <= self.lcm.m,
+            "Position {} is past the end of the code space ({}).",
+            position,
+            self.lcm.m
+        );
+
+        if let Some(block) = &mut self.block {
+            assert!(
+                position >= block.start && position - block.start <= block.len,
+                "Position {} is outside of this generator's partition.",
+                position
+            );
+            block.used = position - block.start;
+        }
+
+        self.lcm.seek(position);
+    }
+
+    /// Advance the generator past the next `n` codes, leaving it in the same
+    /// state as `n` calls to [`ShortCodeGenerator::next_int`] would. Returns
+    /// `self`, so that e.g. `gen.skip_ahead(10).next_vec()` returns the
+    /// eleventh code.
+    ///
+    /// This takes `O(log n)` time when the skipped codes are all in the
+    /// current cycle and no codes need to be filtered out by position
+    /// constraints or `IncreaseLengthPadded`. Otherwise, the codes are
+    /// generated one at a time.
+    pub fn skip_ahead(&mut self, n: u64) -> &mut Self {
+        if n == 0 {
+            return self;
+        }
+
+        // Sequence values used up by the `n` codes: one for the first code of
+        // a fresh partition, and `skip + 1` for every other code.
+        let stride = self.skip.map_or(1, |skip| skip as u64 + 1);
+        let consumed = if self.skip_before_next {
+            n.checked_mul(stride)
+        } else {
+            (n - 1).checked_mul(stride).and_then(|c| c.checked_add(1))
+        };
+
+        let filtered = !self.constraints.is_empty()
+            || matches!(
+                self.exhaustion_strategy.strategy,
+                ExhaustionStrategy::IncreaseLengthPadded
+            ) && self.growth_count > 0;
+
+        let target = match (consumed, &self.block) {
+            (Some(consumed), Some(block)) if block.used + consumed <= block.len => {
+                Some(block.start + block.used + consumed)
+            }
+            (Some(consumed), None) => self
+                .position()
+                .checked_add(consumed)
+                .filter(|&target| target <= self.lcm.m),
+            _ => None,
+        };
+
+        match target {
+            Some(target) if !filtered => {
+                self.seek(target);
+                self.skip_before_next = true;
+                self.generated_count += n;
+            }
+            _ => {
+                for _ in 0..n {
+                    self.next_int();
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Return a copy of this generator advanced past the next `n` codes (see
+    /// [`ShortCodeGenerator::skip_ahead`]), e.g. to preview the codes that
+    /// will be generated later. `self` is unchanged.
+    pub fn lookahead(&self, n: u64) -> Self {
+        let mut gen = self.clone();
+        gen.skip_ahead(n);
+        gen
+    }
+
+    /// Returns `true` if this generator has no more codes to emit in the
+    /// current cycle, taking partitioning into account.
+    fn cycle_exhausted(&self) -> bool {
+        if self.skip.is_some() {
+            let (used, capacity) = self.partition_usage();
+            used >= capacity
+        } else {
+            self.exhausted()
+        }
+    }
+
+    /// Return an iterator over the remaining codes of the current cycle,
+    /// which ends when the cycle is exhausted rather than applying the
+    /// exhaustion strategy.
+    ///
+    /// The iterator's [`ResumableDrain::position`] can be stored, and passed
+    /// to [`ShortCodeGenerator::seek`] on an identically constructed generator
+    /// to continue enumerating where it left off.
+    pub fn resumable_drain(&mut self) -> ResumableDrain<'_, T> {
+        ResumableDrain { generator: self }
+    }
+
+    /// Return `(used, capacity)` for the share of the code space that this
+    /// generator can emit, taking partitioning into account.
+    fn partition_usage(&self) -> (u64, u64) {
+        if let Some(block) = &self.block {
+            return (block.used, block.len);
+        }
+
+        let m = self.lcm.m;
+        let position = self.position();
+
+        match self.skip {
+            Some(skip) => {
+                let stride = skip as u64 + 1;
+
+                // The sequence index of the next code this partition would emit,
+                // which identifies the residue class that the partition owns.
+                let next_index = if self.skip_before_next {
+                    position + skip as u64
+                } else {
+                    position
+                };
+                let residue = next_index % stride;
+                let count_below = |n: u64| (n + stride - 1 - residue) / stride;
+
+                (count_below(next_index.min(m)), count_below(m))
+            }
+            None => (position, m),
+        }
+    }
+
+    /// Return the number of values left in the current cycle of the
+    /// sequence, taking partitioning into account. Each code uses up at
+    /// least one value.
+    fn remaining_values(&self) -> u64 {
+        let (used, capacity) = self.partition_usage();
+        capacity.saturating_sub(used)
+    }
+
+    /// Return `(allowed, total)`: of every `total` codes of the current
+    /// length, `allowed` satisfy the position constraints.
+    fn constraint_ratio(&self) -> (u64, u64) {
+        let mut positions: Vec<(u32, Vec<bool>)> = Vec::new();
+        for constraint in &self.constraints {
+            match positions
+                .iter_mut()
+                .find(|(p, _)| *p == constraint.position)
+            {
+                Some((_, allowed)) => {
+                    for (lhs, rhs) in allowed.iter_mut().zip(&constraint.allowed) {
+                        *lhs &= rhs;
+                    }
+                }
+                None => positions.push((constraint.position, constraint.allowed.clone())),
+            }
+        }
+
+        positions
+            .iter()
+            .fold((1, 1), |(allowed, total), (_, symbols)| {
+                let count = symbols.iter().filter(|&&allowed| allowed).count() as u64;
+                (allowed * count, total * self.alphabet.len() as u64)
+            })
+    }
+
+    /// Return the number of codes this generator can emit before the
+    /// current cycle is exhausted, taking partitioning into account.
+    ///
+    /// Codes skipped by position constraints (including banned leading
+    /// symbols) are left out of the count, assuming they are spread evenly
+    /// over the cycle. This is exact for a fresh, unpartitioned generator,
+    /// and an estimate once codes have been generated. Codes skipped by
+    /// `IncreaseLengthPadded` are included in the count.
+    pub fn remaining(&self) -> u64 {
+        let (allowed, total) = self.constraint_ratio();
+        (self.remaining_values() as u128 * allowed as u128 / total as u128) as u64
+    }
+
+    /// Return how many codes can be issued in each of `windows` equal time
+    /// windows (e.g. `365` for daily quotas over a year) without exhausting
+    /// this generator's share of the code space at the current length.
+    ///
+    /// For a generator created by
+    /// [`ShortCodeGenerator::into_partitioned_generators`] or
+    /// [`ShortCodeGenerator::disjoint_partitions`], this divides the
+    /// partition's share rather than [`ShortCodeGenerator::capacity`].
+    /// Codes already issued are not taken into account; see
+    /// [`ShortCodeGenerator::remaining`].
+    ///
+    /// Panics if `windows` is zero.
+    pub fn codes_per_window(&self, windows: u64) -> u64 {
+        assert!(windows > 0, "Number of windows must be positive.");
+        let (_, share) = self.partition_usage();
+        share / windows
+    }
+
+    /// Return the average fraction of symbol positions that differ between
+    /// consecutive codes of the current cycle, as a diagnostic of how related
+    /// consecutive codes look. For codes drawn independently at random, this
+    /// would be `1 - 1 / alphabet_len`; values well below that mean that
+    /// consecutive codes share many symbols, which
+    /// [`ShortCodeGenerator::with_output_whitening`] can help with.
+    ///
+    /// Compares the first 10,000 pairs of codes in the cycle, or the whole
+    /// cycle if it is shorter, including the pair where it wraps around.
+    /// Only the code proper is compared, not any prefix or check symbol.
+    /// Doesn't advance the generator.
+    pub fn consecutive_symbol_change(&self) -> f64 {
+        let alphabet_size = self.alphabet.len() as u64;
+        let pairs = self.lcm.m.min(SYMBOL_CHANGE_SAMPLE);
+        let code = |index: u64| {
+            let value = self.output_value(self.lcm.value_at(index % self.lcm.m));
+            digits(value, alphabet_size, self.length)
+        };
+
+        let mut previous = code(0);
+        let mut changed = 0;
+        for index in 1..=pairs {
+            let next = code(index);
+            changed += previous.iter().zip(&next).filter(|(a, b)| a != b).count();
+            previous = next;
+        }
+
+        changed as f64 / (pairs * self.length as u64) as f64
+    }
+
+    /// Return the fraction of this generator's code space that has been used
+    /// up in the current cycle, between `0.0` and `1.0`.
+    ///
+    /// For a partitioned generator, this is relative to the partition's own
+    /// share of the code space.
+    pub fn utilization(&self) -> f64 {
+        let (used, capacity) = self.partition_usage();
+        if capacity == 0 {
+            1.0
+        } else {
+            used as f64 / capacity as f64
+        }
+    }
+
+    /// Returns `true` if [`ShortCodeGenerator::utilization`] has reached
+    /// `threshold`, e.g. `0.9` to be warned when 90% of codes are used up.
+    pub fn is_near_exhaustion(&self, threshold: f64) -> bool {
+        self.utilization() >= threshold
+    }
+
+    /// Deprecated alias for [`ShortCodeGenerator::next_vec`]. Compiled out by
+    /// the `no-deprecated` feature.
+    #[cfg(not(feature = "no-deprecated"))]
+    #[deprecated(
+        since = "0.1.4",
+        note = "Deprecated to avoid confusion with Iterator::next. Use next_vec instead."
+    )]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Vec<T> {
+        self.next_vec()
+    }
+
+    /// Return the next short code, represented as a vector.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_vec(&mut self) -> Vec<T> {
+        let value = self.next_int();
+        self.render_code(value)
+    }
+
+    /// Render `value` as a code, with the epoch prefix if there is one.
+    fn render_code(&self, value: u64) -> Vec<T> {
+        let alphabet_size = self.alphabet.len() as u64;
+        let mut result = Vec::new();
+
+        if let Some(index) = self.partition_marker {
+            result.push(index as u64);
+        }
+        if let Some(epoch) = &self.epoch {
+            // Most significant symbol first, so that prefixes sort by epoch.
+            let mut prefix = digits(epoch.value, alphabet_size, epoch.symbols);
+            prefix.reverse();
+            result.extend(prefix);
+        }
+        result.extend(std::iter::repeat_n(0, self.padding_length()));
+        result.extend(digits(value, alphabet_size, self.length));
+
+        if self.check_symbol {
+            result.push(checksum::check_digit(&result, alphabet_size));
+        }
+        #[cfg(feature = "signature")]
+        if let Some(sig) = &self.signature {
+            let symbols = sig.symbols as usize;
+            let key = sig
+                .key
+                .as_ref()
+                .expect("Signature key is not set. Call with_signature after deserializing.");
+            result.extend(signature::sign(key, &result, alphabet_size, symbols));
+        }
+
+        result
+            .into_iter()
+            .map(|digit| self.alphabet[digit as usize].clone())
+            .collect()
+    }
+
+    /// Return the next short code together with the index in the current
+    /// cycle at which it was generated, which is what
+    /// [`ShortCodeGenerator::decode_vec`] would return for it. Cheaper than
+    /// decoding the code afterwards.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_with_index(&mut self) -> (Vec<T>, u64) {
+        let code = self.next_vec();
+        (code, self.position() - 1)
+    }
+
+    /// Return the next short code, wrapped with its place in the sequence so
+    /// that codes compare by when they were generated rather than by their
+    /// symbols. See [`OrderedCode`].
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_ordered(&mut self) -> OrderedCode<T> {
+        let code = self.next_vec();
+        OrderedCode {
+            code,
+            growth_count: self.growth_count,
+            position: self.position() - 1,
+        }
+    }
+
+    /// Return an iterator of codes that calls `on_wrap` with the number of
+    /// completed cycles each time the sequence wraps around under
+    /// [`ExhaustionStrategy::Cycle`], before yielding the first code of the
+    /// new cycle. For example, this can be used to bump an epoch or log a
+    /// warning.
+    ///
+    /// Each item is equivalent to a `next_vec` call, so the iterator never
+    /// ends on its own; under other strategies `on_wrap` is never called.
+    pub fn cycling_iter<'a, F: FnMut(u64) + 'a>(
+        &'a mut self,
+        mut on_wrap: F,
+    ) -> impl Iterator<Item = Vec<T>> + 'a {
+        std::iter::repeat_with(move || {
+            let (value, wrapped) = self.next_int_checked();
+            if wrapped {
+                on_wrap(self.cycle_count);
+            }
+            self.render_code(value)
+        })
+    }
+
+    /// Return an iterator that walks the sequence backwards from the current
+    /// position, yielding the codes generated so far in the current cycle
+    /// from most to least recent. The iterator ends at the start of the cycle
+    /// (or of the block of a generator created by
+    /// [`ShortCodeGenerator::disjoint_partitions`]).
+    ///
+    /// Each yielded code is returned to the pool, as with
+    /// [`ShortCodeGenerator::unget`], so after reversing `n` steps the next
+    /// `n` calls to `next_vec` generate the same codes again.
+    ///
+    /// Panics if the generator was created by
+    /// [`ShortCodeGenerator::into_partitioned_generators`].
+    pub fn rev(&mut self) -> impl Iterator<Item = Vec<T>> + '_ {
+        assert!(
+            self.skip.is_none(),
+            "Can't reverse a generator created by into_partitioned_generators."
+        );
+
+        std::iter::from_fn(move || loop {
+            let at_start = match &self.block {
+                Some(block) => block.used == 0,
+                None => self.position() == 0,
+            };
+            if at_start {
+                return None;
+            }
+
+            self.lcm.prev();
+            if let Some(block) = &mut self.block {
+                block.used -= 1;
+            }
+
+            let value = self.output_value(self.lcm.peek());
+            if !self.reserved_for_padding(value) && self.satisfies_constraints(value) {
+                self.generated_count = self.generated_count.saturating_sub(1);
+                return Some(self.render_code(value));
+            }
+        })
+    }
+
+    /// Return the next `n` short codes, represented as vectors.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_n(&mut self, n: usize) -> Vec<Vec<T>> {
+        (0..n).map(|_| self.next_vec()).collect()
+    }
+
+    /// Return the next short code whose first `k` symbols differ from those
+    /// of the previous code, skipping codes that share the prefix. The `k`
+    /// symbols are counted on the code as rendered, including a partition
+    /// marker (see [`ShortCodeGenerator::with_partition_marker`]) and padding
+    /// (see [`ShortCodeGenerator::with_display_width`]), but not epoch
+    /// prefixes set by [`ShortCodeGenerator::with_epoch`].
+    ///
+    /// Skipped codes are used up, so this reduces the number of codes
+    /// available: on average, one in `alphabet_len ^ k` codes is skipped. If
+    /// no suitable code is found within 1000 codes (e.g. because `k` is at
+    /// least the code length and the generator is cycling through a single
+    /// code), the last code generated is returned anyway. The exhaustion
+    /// strategy applies as usual while skipping.
+    ///
+    /// Panics if `k` doesn't reach past the partition marker and padding,
+    /// which are the same for every code.
+    pub fn next_distinct_prefix(&mut self, k: usize) -> Vec<T> {
+        let fixed = self.partition_marker.is_some() as usize + self.padding_length();
+        assert!(
+            k > fixed,
+            "Prefix of {} symbols doesn't reach past the {} marker and padding symbols.",
+            k,
+            fixed
+        );
+
+        // The previous code is the value just before the next one in the
+        // sequence, unless it was returned with `unget`.
+        let previous = self.skip_before_next.then(|| {
+            let value = self.lcm.jump(self.lcm.peek(), self.lcm.m - 1);
+            self.rendered_prefix(self.output_value(value), k)
+        });
+
+        let mut value = self.next_int();
+        for _ in 1..MAX_PREFIX_ATTEMPTS {
+            if previous.as_ref() != Some(&self.rendered_prefix(value, k)) {
+                break;
+            }
+            value = self.next_int();
+        }
+
+        self.render_code(value)
+    }
+
+    /// Return the first `k` symbols (as indices into the alphabet) that
+    /// `value` is rendered with, leaving out any epoch prefix.
+    fn rendered_prefix(&self, value: u64, k: usize) -> Vec<u64> {
+        let mut result = Vec::new();
+        if let Some(index) = self.partition_marker {
+            result.push(index as u64);
+        }
+        result.extend(std::iter::repeat_n(0, self.padding_length()));
+        result.extend(digits(value, self.alphabet.len() as u64, self.length));
+        result.truncate(k);
+        result
+    }
+
+    /// Generate codes until one satisfies `pred`, and return it. Codes that
+    /// don't match are used up, as with `next_vec`. `pred` is given codes as
+    /// `next_vec` returns them, including any epoch prefix or check symbol.
+    ///
+    /// Only the rest of the current cycle is scanned (see
+    /// [`ShortCodeGenerator::remaining`]): if no code matches, this returns
+    /// `None`, leaving the cycle exhausted, instead of applying the
+    /// exhaustion strategy and scanning forever. This also holds for values
+    /// skipped because of position constraints.
+    pub fn next_matching(&mut self, pred: impl Fn(&[T]) -> bool) -> Option<Vec<T>> {
+        loop {
+            let value = self.next_value(true)?;
+            let code = self.render_code(value);
+            if pred(&code) {
+                return Some(code);
+            }
+        }
+    }
+
+    /// Return an iterator of the next `n` short codes, generating each one
+    /// only when it is requested. Each item is equivalent to a `next_vec`
+    /// call, so the generator advances once per item consumed: `n` times if
+    /// the iterator is exhausted, and fewer if it is dropped early.
+    pub fn take_n(&mut self, n: usize) -> impl Iterator<Item = Vec<T>> + '_ {
+        (0..n).map(move |_| self.next_vec())
+    }
+
+    /// Set the exhaustion strategy of this short code generator. Preserves
+    /// other state.
+    pub fn exhaustion_strategy(mut self, strategy: ExhaustionStrategy) -> Self {
+        self.exhaustion_strategy = StrategySetting {
+            strategy,
+            unrecognized: false,
+        };
+        self
+    }
+
+    /// Set a hook that [`ExhaustionStrategy::WarnThenPanic`] calls with the
+    /// number of remaining codes when 99% of the codes have been used. It is
+    /// called once, by the `next_*` call that crosses the threshold. The hook
+    /// is not serialized, so it must be set again on a deserialized
+    /// generator. Preserves other state.
+    pub fn with_exhaustion_warning(mut self, hook: fn(u64)) -> Self {
+        self.warning_hook = Some(hook);
+        self
+    }
+
+    /// Render `index` as an extra symbol in front of each code (the symbol at
+    /// that index of the alphabet), so that the partition that issued a code
+    /// can be seen without decoding it, e.g. for routing in a sharded
+    /// deployment. Use it on partition `index` of the `total` generators
+    /// returned by [`ShortCodeGenerator::into_partitioned_generators`] or
+    /// [`ShortCodeGenerator::disjoint_partitions`].
+    ///
+    /// This makes codes one symbol longer, in front of any epoch prefix; the
+    /// rest of the code is unchanged. [`ShortCodeGenerator::partition_of`]
+    /// reads the marker directly when given a whole code.
+    ///
+    /// Panics if `index` is not less than `total`, if `total` is greater than
+    /// the alphabet size, if `total` doesn't match the number of
+    /// partitions of a generator created by `into_partitioned_generators`,
+    /// or if leading symbols are banned with
+    /// [`ShortCodeGenerator::with_banned_leading`]. Preserves other state.
+    pub fn with_partition_marker(mut self, index: u32, total: u32) -> Self {
+        assert!(
+            !self.has_banned_leading(),
+            "Can't add a partition marker in front of banned leading symbols."
+        );
+        assert!(
+            index < total,
+            "Partition index {} is out of range for {} partitions.",
+            index,
+            total
+        );
+        assert!(
+            total as usize <= self.alphabet.len(),
+            "Can't mark {} partitions with an alphabet of {} symbols.",
+            total,
+            self.alphabet.len()
+        );
+        if let Some(skip) = self.skip {
+            assert_eq!(
+                skip as u64 + 1,
+                total as u64,
+                "Generator is one of {} partitions, not {}.",
+                skip as u64 + 1,
+                total
+            );
+        }
+
+        self.partition_marker = Some(index);
+        self
+    }
+
+    /// Pass each value through a fixed nonlinear permutation before rendering
+    /// it as a code. The underlying sequence is linear, so codes generated
+    /// close together, such as the last few codes before the sequence wraps
+    /// around, can look patterned; whitening hides this while keeping codes
+    /// distinct.
+    ///
+    /// This is light obfuscation, not encryption: the permutation is the
+    /// same for every generator, so it adds nothing against someone who
+    /// knows this crate. [`ShortCodeGenerator::next_int`] returns the
+    /// whitened value, and [`ShortCodeGenerator::decode_vec`] inverts the
+    /// whitening.
+    ///
+    /// Panics if the generator has already generated codes, since the rest
+    /// of the sequence could then repeat codes already issued.
+    pub fn with_output_whitening(mut self) -> Self {
+        assert!(
+            !self.has_generated(),
+            "Can't enable output whitening after codes have been generated."
+        );
+        self.whitening = true;
+        self
+    }
+
+    /// Choose how the sequence of integers rendered as codes is produced; see
+    /// [`IntegerSource`]. The default is [`IntegerSource::LcmScrambled`].
+    /// Every source generates each code exactly once per cycle, and works
+    /// with the other options, such as partitioning, position constraints
+    /// and [`ShortCodeGenerator::decode_vec`].
+    ///
+    /// Unscrambled sources start from the code made up entirely of the first
+    /// symbol, and ignore the rng.
+    ///
+    /// Panics if the generator has already generated codes, since the rest
+    /// of the sequence could then repeat codes already issued.
+    pub fn integer_source(mut self, source: IntegerSource) -> Self {
+        assert!(
+            !self.has_generated(),
+            "Can't change the integer source after codes have been generated."
+        );
+
+        if source != self.integer_source {
+            self.integer_source = source;
+            self.reseed(self.lcm.m);
+        }
+        self
+    }
+
+    /// Append a check symbol to each code, computed from the rest of the code
+    /// (including any epoch prefix) with the Luhn mod N algorithm. This
+    /// catches any single mistyped symbol and most swaps of adjacent symbols;
+    /// see [`ShortCodeGenerator::has_valid_check_symbol`].
+    ///
+    /// This makes codes from `next_vec` and the other `next_*` methods one
+    /// symbol longer. Preserves other state.
+    pub fn with_check_symbol(mut self) -> Self {
+        self.check_symbol = true;
+        self
+    }
+
+    /// Left-pad codes from `next_vec` and the other `next_*` methods with the
+    /// first symbol of the alphabet to `width` symbols (not counting any
+    /// epoch prefix, check symbol or signature), e.g. for fixed-width fields
+    /// that must keep fitting as [`ExhaustionStrategy::IncreaseLength`]
+    /// grows the codes. Only the rendering changes, not the sequence of
+    /// codes; methods like [`ShortCodeGenerator::decode_vec`] take and return
+    /// codes without the padding.
+    ///
+    /// A padded short code can be the same as a longer code issued after the
+    /// length increases (e.g. `"07"` at length 1 and 2), so use
+    /// [`ExhaustionStrategy::IncreaseLengthPadded`] if codes must stay
+    /// distinct across the increase. Codes are never truncated: once the
+    /// length exceeds `width`, codes are longer than `width`. Use
+    /// [`ShortCodeGenerator::with_max_length`] to prevent that.
+    ///
+    /// Panics if `width` is less than the current length, or if it pads
+    /// codes whose leading symbols are banned with
+    /// [`ShortCodeGenerator::with_banned_leading`]. Preserves other state.
+    pub fn with_display_width(mut self, width: usize) -> Self {
+        assert!(
+            width >= self.length as usize,
+            "Display width {} is less than the code length {}.",
+            width,
+            self.length
+        );
+        assert!(
+            width == self.length as usize || !self.has_banned_leading(),
+            "Can't pad codes in front of banned leading symbols."
+        );
+        self.display_width = Some(width as u32);
+        self
+    }
+
+    /// Prefix each code with an epoch of `epoch_symbols` symbols, starting
+    /// at zero and incremented by [`ShortCodeGenerator::advance_epoch`]. For
+    /// example, several nodes that each advance the epoch on restart, or on a
+    /// timer, get codes that differ from earlier epochs' codes by prefix even
+    /// if the rest of the code repeats.
+    ///
+    /// This makes codes from `next_vec` and the other `next_*` methods
+    /// `epoch_symbols` longer. Methods that take or return codes without
+    /// generating them, like [`ShortCodeGenerator::decode_vec`] and
+    /// [`ShortCodeGenerator::code_at`], work with the code after the prefix.
+    ///
+    /// Panics if `epoch_symbols` is zero. Preserves other state.
+    pub fn with_epoch(mut self, epoch_symbols: usize) -> Self {
+        assert!(epoch_symbols > 0, "Expected at least one epoch symbol.");
+        self.epoch = Some(Epoch {
+            symbols: epoch_symbols as u32,
+            value: 0,
+        });
+        self
+    }
+
+    /// Advance the epoch set by [`ShortCodeGenerator::with_epoch`] by one.
+    ///
+    /// Panics if no epoch is set, or if the next epoch doesn't fit in the
+    /// epoch's symbols.
+    pub fn advance_epoch(&mut self) {
+        let alphabet_len = self.alphabet.len();
+        let epoch = self
+            .epoch
+            .as_mut()
+            .expect("Can't advance the epoch of a generator without one.");
+
+        let capacity = checked_capacity(alphabet_len, epoch.symbols as usize).unwrap_or(u64::MAX);
+        assert!(
+            epoch.value + 1 < capacity,
+            "Epoch overflows {} symbols.",
+            epoch.symbols
+        );
+        epoch.value += 1;
+    }
+
+    /// Use `c` as the increment of the underlying linear congruential
+    /// generator instead of 1, e.g. to match the sequence of another system.
+    /// The increment is kept when the length increases.
+    ///
+    /// Panics if `c` is not coprime to [`ShortCodeGenerator::capacity`] (and
+    /// therefore to the alphabet size), since the sequence would then skip
+    /// codes, or if the generator has already generated codes.
+    pub fn with_increment(mut self, c: u64) -> Self {
+        assert!(
+            !self.has_generated(),
+            "Can't change the increment after codes have been generated."
+        );
+
+        let (first, a, _) = self.lcm.params();
+        let lcm = LinearCongruentMultiplier::new(first, self.lcm.m, c, a);
+        assert!(
+            lcm.has_full_period(),
+            "Increment {} is not coprime to the number of codes ({}).",
+            c,
+            self.lcm.m
+        );
+
+        self.lcm = lcm;
+        if let Some(block) = &self.block {
+            self.lcm.seek(block.start);
+        }
+        self.increment = Some(c);
+        self
+    }
+
+    /// Cap the length that [`ExhaustionStrategy::IncreaseLength`] (or
+    /// `IncreaseLengthPadded`) can grow codes to. Once codes of length `max`
+    /// are exhausted, the generator panics instead of growing; use
+    /// [`ShortCodeGenerator::max_length_fallback`] to cycle instead.
+    ///
+    /// By default there is no cap. Preserves other state.
+    pub fn with_max_length(mut self, max: usize) -> Self {
+        let fallback = self
+            .max_length
+            .map_or(ExhaustionStrategy::Panic, |max_length| max_length.fallback);
+        self.max_length = Some(MaxLength {
+            length: max as u32,
+            fallback,
+        });
+        self
+    }
+
+    /// Set what happens when codes at the length set by
+    /// [`ShortCodeGenerator::with_max_length`] are exhausted. Only
+    /// [`ExhaustionStrategy::Cycle`] and [`ExhaustionStrategy::Panic`] (the
+    /// default) are meaningful; other strategies behave like `Panic`.
+    ///
+    /// Has no effect unless a max length is set. Preserves other state.
+    pub fn max_length_fallback(mut self, fallback: ExhaustionStrategy) -> Self {
+        if let Some(max_length) = &mut self.max_length {
+            max_length.fallback = fallback;
+        }
+        self
+    }
+
+    /// Return the exhaustion strategy set by
+    /// [`ShortCodeGenerator::exhaustion_strategy`], or
+    /// [`ExhaustionStrategy::Panic`] if it was unrecognized when deserializing
+    /// (see [`ShortCodeGenerator::exhaustion_strategy_unrecognized`]).
+    ///
+    /// Once the length cap set by [`ShortCodeGenerator::with_max_length`] is
+    /// reached, the fallback set by
+    /// [`ShortCodeGenerator::max_length_fallback`] applies instead, but this
+    /// still returns the configured strategy.
+    pub fn current_strategy(&self) -> ExhaustionStrategy {
+        self.exhaustion_strategy.strategy
+    }
+
+    /// Returns `true` if this generator was deserialized from state naming an
+    /// exhaustion strategy this version of the crate doesn't know about (e.g.
+    /// state written by a newer version). In that case the generator falls back
+    /// to [`ExhaustionStrategy::Panic`] rather than failing to load, since
+    /// panicking can't cause collisions or unexpected code lengths.
+    ///
+    /// Callers who would rather treat this as a load error can check this
+    /// after deserializing.
+    pub fn exhaustion_strategy_unrecognized(&self) -> bool {
+        self.exhaustion_strategy.unrecognized
+    }
+}
+
+impl<T: Clone + PartialEq> ShortCodeGenerator<T> {
+    /// Replace the alphabet with one of at most as many symbols before any
+    /// codes have been generated, e.g. to fix a misconfigured alphabet
+    /// without reconstructing the generator. The code length and other
+    /// configuration are preserved.
+    ///
+    /// Returns [`AlphabetError::CodesIssued`] if the generator has already
+    /// generated a code (so that codes already handed out stay valid),
+    /// [`AlphabetError::SizeMismatch`] if `new_alphabet` has more symbols than
+    /// the current alphabet, and [`AlphabetError::IncompatibleSetting`] if the
+    /// generator is partitioned, limited to a block, or has position
+    /// constraints, a partition marker or an epoch that refer to the current
+    /// alphabet and can't be carried over. Partitions and constraints should
+    /// be set up after the alphabet is changed, not before. Otherwise, returns
+    /// an error if `new_alphabet` has fewer than two symbols or a repeated
+    /// symbol, or as for [`ShortCodeGenerator::try_with_alphabet_and_rng`].
+    /// The generator is unchanged if an error is returned.
+    pub fn try_shrink_alphabet(&mut self, new_alphabet: Vec<T>) -> Result<(), AlphabetError> {
+        if self.has_generated() {
+            return Err(AlphabetError::CodesIssued);
+        }
+        if new_alphabet.len() > self.alphabet.len() {
+            return Err(AlphabetError::SizeMismatch {
+                expected: self.alphabet.len(),
+                actual: new_alphabet.len(),
+            });
+        }
+        check_distinct(&new_alphabet)?;
+
+        let incompatible = |setting| Err(AlphabetError::IncompatibleSetting { setting });
+        if self.skip.is_some() || self.block.is_some() {
+            return incompatible("partitioning or a reserved block");
+        }
+        if !self.constraints.is_empty() {
+            return incompatible("position constraints");
+        }
+        if self
+            .partition_marker
+            .is_some_and(|index| index as usize >= new_alphabet.len())
+        {
+            return incompatible("a partition marker");
+        }
+        if let Some(epoch) = &self.epoch {
+            let capacity =
+                checked_capacity(new_alphabet.len(), epoch.symbols as usize).unwrap_or(u64::MAX);
+            if epoch.value >= capacity {
+                return incompatible("an epoch");
+            }
+        }
+
+        let m = checked_capacity(new_alphabet.len(), self.length as usize)?;
+        let c = self.increment.unwrap_or(1);
+        let a = lcm::generate_a(new_alphabet.len() as u32) as u64;
+        if !LinearCongruentMultiplier::new(0, m, c, a).has_full_period() {
+            return Err(AlphabetError::UnsupportedSize {
+                alphabet_len: new_alphabet.len(),
+            });
+        }
+
+        self.alphabet = new_alphabet.into();
+        self.reseed(m);
+        Ok(())
+    }
+
+    /// Replace each symbol of the alphabet with the symbol at the same index
+    /// of `new_alphabet`, without changing the sequence of integers the
+    /// generator produces. Unlike
+    /// [`ShortCodeGenerator::try_shrink_alphabet`], this can be done at any
+    /// time, e.g. to change the symbol set of a deployed generator.
+    ///
+    /// Codes issued before and after the change use different symbols for
+    /// the same integers, so a code issued before must be decoded by a
+    /// generator with the old alphabet. Codes issued after don't collide
+    /// with each other, but can look the same as codes issued before.
+    ///
+    /// Settings that refer to symbols follow their index, not the symbol:
+    /// position constraints (including banned leading symbols) and the
+    /// partition marker keep the same indices, so they now allow, ban or
+    /// render the symbols at those indices of `new_alphabet`. This keeps the
+    /// set of integers that are skipped unchanged.
+    ///
+    /// Returns [`AlphabetError::SizeMismatch`] if `new_alphabet` has a
+    /// different number of symbols, or [`AlphabetError::RepeatedSymbol`] if
+    /// it contains a symbol more than once. The generator is unchanged if an
+    /// error is returned.
+    pub fn remap_alphabet(&mut self, new_alphabet: Vec<T>) -> Result<(), AlphabetError> {
+        if new_alphabet.len() != self.alphabet.len() {
+            return Err(AlphabetError::SizeMismatch {
+                expected: self.alphabet.len(),
+                actual: new_alphabet.len(),
+            });
+        }
+        check_distinct(&new_alphabet)?;
+
+        self.alphabet = new_alphabet.into();
+        Ok(())
+    }
+
+    /// Create a short code generator from an iterator of symbols, e.g. a
+    /// range or a filtered or chained iterator, using the given ChaCha12Rng
+    /// random number generator.
+    ///
+    /// Repeated symbols are dropped, keeping the first occurrence, so the
+    /// alphabet never contains duplicates. Returns an error if fewer than
+    /// two distinct symbols remain, or in the other cases described in
+    /// [`ShortCodeGenerator::try_with_alphabet_and_rng`].
+    pub fn from_symbols<I: IntoIterator<Item = T>>(
+        symbols: I,
+        length: usize,
+        rng: ChaCha12Rng,
+    ) -> Result<Self, AlphabetError> {
+        let symbols: Vec<T> = symbols.into_iter().collect();
+        let alphabet = merge_alphabets(&[&symbols]);
+
+        match alphabet.len() {
+            0 => return Err(AlphabetError::Empty),
+            1 => return Err(AlphabetError::SingleSymbol),
+            _ => {}
+        }
+
+        Self::try_with_alphabet_and_rng(alphabet, length, rng)
+    }
+
+    /// Return the number of codes after which `self` and `other` first
+    /// generate different codes, or `None` if they generate the same first
+    /// `max_steps` codes. Neither generator is changed. This is meant for
+    /// checking that generators which should be identical (e.g. one resumed
+    /// from saved state) are.
+    pub fn diverges_from(&self, other: &Self, max_steps: u64) -> Option<u64> {
+        let (mut lhs, mut rhs) = (self.clone(), other.clone());
+        (0..max_steps).find(|_| lhs.next_vec() != rhs.next_vec())
+    }
+
+    /// Only generate codes whose symbol at `position` (counting from the
+    /// first symbol of the code, as returned by `next_vec`) is one of
+    /// `allowed`, e.g. so that codes never start with a digit. Symbols in
+    /// `allowed` that aren't in the alphabet are ignored.
+    ///
+    /// Codes that violate a constraint are skipped, so codes remain unique,
+    /// but the number of codes available before exhaustion is reduced
+    /// accordingly. Generating a code panics if constraints reject too many
+    /// codes in a row.
+    ///
+    /// Panics if `position` is not less than the code length, or if none of
+    /// the symbols in `allowed` are in the alphabet. Preserves other state.
+    pub fn with_position_constraint(mut self, position: usize, allowed: Vec<T>) -> Self {
+        assert!(
+            position < self.length as usize,
+            "Position {} is out of range for codes of length {}.",
+            position,
+            self.length
+        );
+
+        let allowed: Vec<bool> = self
+            .alphabet
+            .iter()
+            .map(|symbol| allowed.contains(symbol))
+            .collect();
+        assert!(
+            allowed.contains(&true),
+            "Expected at least one allowed symbol to be in the alphabet."
+        );
+
+        self.constraints.push(PositionConstraint {
+            position: position as u32,
+            allowed,
+            leading: false,
+        });
+        self
+    }
+
+    /// Only generate codes whose first symbol (after any epoch prefix) is
+    /// not one of `banned`, e.g. to keep codes from colliding with routing
+    /// prefixes. Symbols in `banned` that aren't in the alphabet are ignored.
+    ///
+    /// This is a position constraint (see
+    /// [`ShortCodeGenerator::with_position_constraint`]) on the first
+    /// symbol, so codes with a banned first symbol are skipped and left out
+    /// of [`ShortCodeGenerator::remaining`]. This loses about
+    /// `banned.len() / alphabet_len` of the codes.
+    ///
+    /// Panics if every symbol of the alphabet is banned, or if codes start
+    /// with a partition marker (see
+    /// [`ShortCodeGenerator::with_partition_marker`]) or with padding (see
+    /// [`ShortCodeGenerator::with_display_width`]), since then the first
+    /// symbol of the code is not the first symbol rendered. Preserves other
+    /// state.
+    pub fn with_banned_leading(mut self, banned: Vec<T>) -> Self {
+        assert!(
+            self.partition_marker.is_none() && self.padding_length() == 0,
+            "Can't ban leading symbols of codes that start with a partition marker or padding."
+        );
+
+        let allowed: Vec<bool> = self
+            .alphabet
+            .iter()
+            .map(|symbol| !banned.contains(symbol))
+            .collect();
+        assert!(
+            allowed.contains(&true),
+            "Can't ban every symbol of the alphabet from leading codes."
+        );
+
+        self.constraints.push(PositionConstraint {
+            position: 0,
+            allowed,
+            leading: true,
+        });
+        self
+    }
+
+    /// Convert a code back to the integer it was rendered from (the value
+    /// returned by `next_int`). Returns `None` if the code has the wrong
+    /// length or contains symbols that aren't in the alphabet.
+    fn code_value(&self, code: &[T]) -> Option<u64> {
+        if code.len() != self.length as usize {
+            return None;
+        }
+
+        let alphabet_size = self.alphabet.len() as u64;
+        code.iter().rev().try_fold(0u64, |value, symbol| {
+            let digit = self.alphabet.iter().position(|s| s == symbol)?;
+            Some(value * alphabet_size + digit as u64)
+        })
+    }
+
+    /// Return the part of a generated code that [`ShortCodeGenerator::decode_vec`]
+    /// takes, without the epoch prefix and check symbol. Returns `None` if
+    /// `code` has the wrong length.
+    fn strip_code<'a>(&self, code: &'a [T]) -> Option<&'a [T]> {
+        if !self.accepts_length(code.len()) {
+            return None;
+        }
+
+        let start = self.prefix_length();
+        let padding = &code[start - self.padding_length()..start];
+        if !padding.iter().all(|symbol| *symbol == self.alphabet[0]) {
+            return None;
+        }
+
+        Some(&code[start..start + self.length as usize])
+    }
+
+    /// Returns `true` if `code` (as generated by `next_vec`) ends in the
+    /// correct check symbol. Always returns `false` if check symbols are not
+    /// enabled with [`ShortCodeGenerator::with_check_symbol`], or if `code`
+    /// has the wrong length or symbols that aren't in the alphabet.
+    pub fn has_valid_check_symbol(&self, code: &[T]) -> bool {
+        if !self.check_symbol || !self.accepts_length(code.len()) {
+            return false;
+        }
+
+        let digits: Option<Vec<u64>> = code
+            .iter()
+            .map(|symbol| {
+                let digit = self.alphabet.iter().position(|s| s == symbol)?;
+                Some(digit as u64)
+            })
+            .collect();
+
+        digits.is_some_and(|digits| {
+            let signed = &digits[..digits.len() - self.signature_length()];
+            checksum::is_valid(signed, self.alphabet.len() as u64)
+        })
+    }
+
+    /// Return the index in the current cycle at which `code` is generated,
+    /// i.e. the [`ShortCodeGenerator::position`] just before it is generated.
+    ///
+    /// Returns `None` if the code couldn't have been generated at the current
+    /// length.
+    pub fn decode_vec(&self, code: &[T]) -> Option<u64> {
+        self.decode_with_factors(code, &lcm::prime_factors(self.lcm.m))
+    }
+
+    /// Like [`ShortCodeGenerator::decode_vec`], for many codes at once.
+    /// Returns the index of each code, in the same order as `codes`.
+    pub fn decode_many(&self, codes: &[Vec<T>]) -> Vec<Option<u64>> {
+        let factors = lcm::prime_factors(self.lcm.m);
+        codes
+            .iter()
+            .map(|code| self.decode_with_factors(code, &factors))
+            .collect()
+    }
+
+    /// Decode `code`, given the prime factors of the modulus.
+    fn decode_with_factors(&self, code: &[T], factors: &[u64]) -> Option<u64> {
+        let mut value = self.code_value(code)?;
+        if self.whitening {
+            value = whitening::unwhiten(value, self.alphabet.len() as u64, self.length);
+        }
+        if self.integer_source == IntegerSource::Halton {
+            value = reverse_digits(value, self.alphabet.len() as u64, self.length);
+        }
+        let lcm_value = add_mod(value, self.lcm.m - self.offset, self.lcm.m);
+        Some(self.lcm.index_of_with_factors(lcm_value, factors))
+    }
+
+    /// Return the codes just before and just after `code` in the current
+    /// cycle, wrapping around at the ends of the cycle as
+    /// [`ExhaustionStrategy::Cycle`] does. This is meant for understanding
+    /// the order in which codes are generated.
+    ///
+    /// Like [`ShortCodeGenerator::decode_vec`], `code` is a code without any
+    /// epoch prefix, check symbol or partition marker. Returns `None` if it
+    /// couldn't have been generated at the current length.
+    pub fn neighbors(&self, code: &[T]) -> Option<(Vec<T>, Vec<T>)> {
+        let index = self.decode_vec(code)?;
+        let m = self.lcm.m;
+        Some((
+            self.code_at(index.checked_sub(1).unwrap_or(m - 1)),
+            self.code_at(add_mod(index, 1, m)),
+        ))
+    }
+
+    /// Return which of `total` partitions created by
+    /// [`ShortCodeGenerator::into_partitioned_generators`] issues `code`,
+    /// where `self` is the generator that was partitioned (or any of the
+    /// partitions).
+    ///
+    /// If the generator has a partition marker (see
+    /// [`ShortCodeGenerator::with_partition_marker`]), `code` may also be a
+    /// whole code as generated, in which case the partition is read from the
+    /// marker without decoding the code.
+    ///
+    /// Returns `None` if `total` is zero, or if the code couldn't have been
+    /// generated at the current length.
+    pub fn partition_of(&self, code: &[T], total: u32) -> Option<u32> {
+        if total == 0 {
+            return None;
+        }
+
+        if self.partition_marker.is_some() && self.accepts_length(code.len()) {
+            let index = self.alphabet.iter().position(|s| *s == code[0])? as u32;
+            return (index < total).then_some(index);
+        }
+
+        let index = self.decode_vec(code)?;
+        Some((index % total as u64) as u32)
+    }
+
+    /// Return how many codes of the current length start with `prefix`, e.g.
+    /// for sizing prefix-based routing. Like
+    /// [`ShortCodeGenerator::decode_vec`], this counts codes without any
+    /// epoch prefix, check symbol or partition marker, and ignores position
+    /// constraints.
+    ///
+    /// Every code is generated once per cycle, so this is
+    /// `alphabet_len ^ (length - prefix.len())`, or 0 if `prefix` is longer
+    /// than the code or contains symbols that aren't in the alphabet.
+    pub fn count_with_prefix(&self, prefix: &[T]) -> u64 {
+        if prefix.len() > self.length as usize
+            || !prefix.iter().all(|symbol| self.alphabet.contains(symbol))
+        {
+            return 0;
+        }
+
+        let free = self.length - prefix.len() as u32;
+        (self.alphabet.len() as u64).pow(free)
+    }
+}
+
+impl<T: Clone + Eq + std::hash::Hash> ShortCodeGenerator<T> {
+    /// Return the digit value of each symbol of the alphabet. A code of
+    /// length `n` with symbols `s[0], .., s[n - 1]` represents the integer
+    /// `sum(map[s[i]] * alphabet_len ^ i)`, i.e. the first symbol is the least
+    /// significant digit. This is the value returned by
+    /// [`ShortCodeGenerator::next_int`] for the code. Any epoch prefix or
+    /// check symbol is not part of `s`.
+    pub fn symbol_index_map(&self) -> std::collections::HashMap<T, usize> {
+        self.alphabet
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| (symbol.clone(), index))
+            .collect()
+    }
+
+    /// Count how often each symbol of the alphabet appears, across all
+    /// positions, in the codes of one full cycle. Symbols that never appear
+    /// are included with a count of zero. Any epoch prefix or check symbol is
+    /// not counted.
+    ///
+    /// Every code of the current length is generated exactly once per cycle,
+    /// so each symbol is expected to appear `capacity * length / alphabet_len`
+    /// times; this is a way to check that. It takes time proportional to
+    /// [`ShortCodeGenerator::capacity`], and doesn't advance the generator.
+    pub fn symbol_frequency(&self) -> std::collections::HashMap<T, u64> {
+        let mut frequency: std::collections::HashMap<T, u64> = self
+            .alphabet
+            .iter()
+            .map(|symbol| (symbol.clone(), 0))
+            .collect();
+
+        for index in 0..self.lcm.m {
+            for symbol in self.code_at(index) {
+                *frequency.entry(symbol).or_default() += 1;
+            }
+        }
+        frequency
+    }
+
+    /// Generate `count` codes and insert them into `set`, returning the
+    /// number that weren't already in it.
+    ///
+    /// Codes are unique within a cycle, so if `set` only holds codes from
+    /// this generator every insert succeeds while `count` is at most
+    /// [`ShortCodeGenerator::remaining`]. Beyond that,
+    /// [`ExhaustionStrategy::Cycle`] repeats earlier codes and fewer are
+    /// inserted.
+    pub fn fill_unique(
+        &mut self,
+        set: &mut std::collections::HashSet<Vec<T>>,
+        count: usize,
+    ) -> usize {
+        (0..count).filter(|_| set.insert(self.next_vec())).count()
+    }
+}
+
+impl<T: Clone + std::hash::Hash> ShortCodeGenerator<T> {
+    /// Return a hash of the configuration that determines this generator's
+    /// sequence: the alphabet, code length, and the parameters of the
+    /// sequence drawn from the rng. Generators built from the same alphabet,
+    /// length and rng seed have equal fingerprints, so comparing them detects
+    /// configuration drift, e.g. before applying a
+    /// [`ShortCodeGenerator::cursor_token`] from another deployment.
+    ///
+    /// The fingerprint doesn't depend on the platform, but changes when the
+    /// length increases. Position, partitioning and other
+    /// options are not included.
+    pub fn config_fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = token::Fnv::default();
+        self.alphabet.hash(&mut hasher);
+        self.length.hash(&mut hasher);
+        self.lcm.m.hash(&mut hasher);
+        self.lcm.params().hash(&mut hasher);
+        self.offset.hash(&mut hasher);
+        self.whitening.hash(&mut hasher);
+        (self.integer_source as u32).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Return a hash of the configuration that a cursor token is only valid
+    /// for: the alphabet, length, sequence and partitioning.
+    fn fingerprint(&self) -> u32 {
+        use std::hash::{Hash, Hasher};
+
+        let (block_start, block_len) = self.block.map_or((0, 0), |block| (block.start, block.len));
+        let mut alphabet = token::Fnv::default();
+        self.alphabet.hash(&mut alphabet);
+
+        let config = [
+            alphabet.finish(),
+            self.length as u64,
+            self.offset,
+            self.lcm.value_at(0),
+            self.lcm.value_at(1),
+            self.skip.map_or(0, |skip| skip as u64 + 1),
+            block_start,
+            block_len,
+            self.whitening as u64,
+            self.integer_source as u64,
+        ];
+
+        token::fingerprint(&config)
+    }
+
+    /// Return a short URL-safe token recording where this generator is in its
+    /// sequence, which can be restored with
+    /// [`ShortCodeGenerator::apply_cursor_token`].
+    ///
+    /// Unlike serializing the whole generator, the token doesn't contain the
+    /// alphabet or rng state, so it can only be applied to a generator with
+    /// the same configuration (e.g. one deserialized from the same state, or
+    /// built from the same alphabet, length and rng seed). Interleaved
+    /// partitions from [`ShortCodeGenerator::into_partitioned_generators`]
+    /// share a configuration, so a partition's token should only be applied
+    /// to that same partition.
+    pub fn cursor_token(&self) -> String {
+        let mut bytes = Vec::with_capacity(13);
+        bytes.extend_from_slice(&self.fingerprint().to_be_bytes());
+        bytes.extend_from_slice(&self.position().to_be_bytes());
+        bytes.push(self.skip_before_next as u8);
+        token::encode(&bytes)
+    }
+
+    /// Move this generator to the point recorded by
+    /// [`ShortCodeGenerator::cursor_token`].
+    ///
+    /// Returns [`TokenError::Mismatch`] if the token was produced by a
+    /// generator with a different alphabet, length, rng or partitioning
+    /// (including one whose length has since increased), and
+    /// [`TokenError::Malformed`] if it isn't a cursor token at all. The
+    /// generator is unchanged if an error is returned.
+    pub fn apply_cursor_token(&mut self, token: &str) -> Result<(), TokenError> {
+        let bytes = token::decode(token).ok_or(TokenError::Malformed)?;
+        let bytes: [u8; 13] = bytes.try_into().map_err(|_| TokenError::Malformed)?;
+
+        let fingerprint = u32::from_be_bytes(bytes[0..4].try_into().unwrap());
+        let position = u64::from_be_bytes(bytes[4..12].try_into().unwrap());
+        let skip_before_next = match bytes[12] {
+            0 => false,
+            1 => true,
+            _ => return Err(TokenError::Malformed),
+        };
+
+        if fingerprint != self.fingerprint() {
+            return Err(TokenError::Mismatch);
+        }
+
+        let in_range = match &self.block {
+            Some(block) => position >= block.start && position - block.start <= block.len,
+            None => position <= self.lcm.m,
+        };
+        if !in_range {
+            return Err(TokenError::Malformed);
+        }
+
+        self.seek(position);
+        self.skip_before_next = skip_before_next;
+        Ok(())
+    }
+}
+
+/// Iterator over the remaining codes of a generator's current cycle. Created
+/// by [`ShortCodeGenerator::resumable_drain`].
+pub struct ResumableDrain<'a, T: Clone> {
+    generator: &'a mut ShortCodeGenerator<T>,
+}
+
+impl<T: Clone> ResumableDrain<'_, T> {
+    /// Return the generator's current position, for use with
+    /// [`ShortCodeGenerator::seek`] to resume the drain later.
+    pub fn position(&self) -> u64 {
+        self.generator.position()
+    }
+}
+
+impl<T: Clone> Iterator for ResumableDrain<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.generator.cycle_exhausted() {
+            None
+        } else {
+            Some(self.generator.next_vec())
+        }
+    }
+}
+
+/// A code paired with the point at which it was generated. Created by
+/// [`ShortCodeGenerator::next_ordered`].
+///
+/// Codes from the same generator are ordered by when they were generated,
+/// including across length increases. The order restarts when an
+/// [`ExhaustionStrategy::Cycle`] generator wraps around, and codes from
+/// different generators (including partitions) are ordered by their index in
+/// the shared sequence.
+#[derive(Clone, Debug)]
+pub struct OrderedCode<T> {
+    code: Vec<T>,
+    growth_count: u32,
+    position: u64,
+}
+
+impl<T> OrderedCode<T> {
+    /// Return the code.
+    pub fn code(&self) -> &[T] {
+        &self.code
+    }
+
+    /// Return the index in the cycle at which the code was generated (see
+    /// [`ShortCodeGenerator::code_at`]).
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Unwrap the code.
+    pub fn into_code(self) -> Vec<T> {
+        self.code
+    }
+
+    fn key(&self) -> (u32, u64) {
+        (self.growth_count, self.position)
+    }
+}
+
+impl<T> PartialEq for OrderedCode<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<T> Eq for OrderedCode<T> {}
+
+impl<T> PartialOrd for OrderedCode<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for OrderedCode<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// The sequence of integers that a [`ShortCodeGenerator`] renders as codes.
+/// Set by [`ShortCodeGenerator::integer_source`].
+///
+/// Each source visits every integer below [`ShortCodeGenerator::capacity`]
+/// exactly once per cycle; they differ in how predictable and how ordered
+/// the codes are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IntegerSource {
+    /// A linear congruential sequence with a random starting point and
+    /// offset, drawn from the rng. Codes look unrelated to each other. This
+    /// is the default.
+    #[default]
+    LcmScrambled,
+
+    /// A plain counter from zero. Each code is predictable from the one
+    /// before it. Codes are rendered least significant symbol first, like all
+    /// codes from [`ShortCodeGenerator`], so the first symbol changes fastest.
+    Sequential,
+
+    /// The van der Corput sequence, as in [`HaltonGenerator`]: a counter with
+    /// its digits reversed, so that any run of consecutive codes is spread
+    /// evenly over the code space. Predictable, like `Sequential`.
+    Halton,
+}
+
+/// Determines what happens when all codes (for a given alphabet and length) have
+/// been exhausted.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ExhaustionStrategy {
+    /// Repeat the sequences of short codes, starting with the first one.
+    /// This guarantees a collision if codes live indefinitely, but can be useful
+    /// when codes are used temporarily as it ensures that a code is never reused
+    /// until *all other possible codes* have been used between usages.
+    Cycle,
+
+    /// Increase the length of the sequence, and continue. This is the default and
+    /// avoids collisions.
+    #[default]
+    IncreaseLength,
+
+    /// Like `IncreaseLength`, but for consumers that store codes in fixed-width
+    /// fields. Codes issued before an increase are considered to be left-padded
+    /// with the first symbol of the alphabet to the new length (e.g. `"7"`
+    /// becomes `"07"`), so after an increase no code starting with the first
+    /// symbol is issued. This keeps padded old codes and new codes distinct,
+    /// at the cost of `1 / alphabet size` of the space at each new length.
+    ///
+    /// Requires an alphabet of at least two symbols.
+    IncreaseLengthPadded,
+
+    /// Like `IncreaseLength`, but for builds without the `getrandom` feature
+    /// that may have no rng to seed the longer sequence with (generators
+    /// deserialized from pre-0.1.4 state). Instead of panicking, the seed is
+    /// then derived from the generator's current state, so anyone who knows
+    /// the state can predict the sequence after the increase. When an rng or
+    /// `getrandom` is available, this behaves exactly like `IncreaseLength`.
+    IncreaseLengthDeterministic,
+
+    /// Panics. This is a fail-fast option
+    /// for cases where you don't expect the codes to ever become exhausted, and
+    /// either creating a collision or increasing the length of the code would be
+    /// incorrect behavior.
+    Panic,
+
+    /// Like `Panic`, but gives operators a heads-up first: once 99% of the
+    /// codes have been used, `is_near_exhaustion(0.99)` returns `true` and
+    /// the hook set by [`ShortCodeGenerator::with_exhaustion_warning`], if
+    /// any, is called once.
+    WarnThenPanic,
+}
+
+/// The exhaustion strategy of a generator, and whether it was substituted for
+/// one that couldn't be deserialized. Serializes as the bare strategy.
+#[derive(Clone, Copy, Debug, Default)]
+struct StrategySetting {
+    strategy: ExhaustionStrategy,
+    unrecognized: bool,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for StrategySetting {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.strategy.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for StrategySetting {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Mirrors `ExhaustionStrategy`, with a catch-all for variants added
+        /// in later versions.
+        #[derive(Deserialize)]
+        #[serde(rename = "ExhaustionStrategy")]
+        enum Repr {
+            Cycle,
+            IncreaseLength,
+            IncreaseLengthPadded,
+            IncreaseLengthDeterministic,
+            Panic,
+            WarnThenPanic,
+            #[serde(other)]
+            Unrecognized,
+        }
+
+        let (strategy, unrecognized) = match Repr::deserialize(deserializer)? {
+            Repr::Cycle => (ExhaustionStrategy::Cycle, false),
+            Repr::IncreaseLength => (ExhaustionStrategy::IncreaseLength, false),
+            Repr::IncreaseLengthPadded => (ExhaustionStrategy::IncreaseLengthPadded, false),
+            Repr::IncreaseLengthDeterministic => {
+                (ExhaustionStrategy::IncreaseLengthDeterministic, false)
+            }
+            Repr::Panic => (ExhaustionStrategy::Panic, false),
+            Repr::WarnThenPanic => (ExhaustionStrategy::WarnThenPanic, false),
+            Repr::Unrecognized => (ExhaustionStrategy::Panic, true),
+        };
+
+        Ok(StrategySetting {
+            strategy,
+            unrecognized,
+        })
+    }
+}
+
+#[cfg(not(feature = "getrandom"))]
+#[cfg(test)]
+mod no_entropy_tests {
+    use super::*;
+
+    #[test]
+    fn test_increase_length_deterministic() {
+        let gen = |strategy| {
+            let mut gen = ShortCodeGenerator::with_alphabet_and_rng(
+                "0123456789".chars().collect(),
+                2,
+                ChaCha12Rng::from_seed([7; 32]),
+            )
+            .exhaustion_strategy(strategy);
+            // As for a generator deserialized from pre-0.1.4 state.
+            gen.rng = None;
+            gen
+        };
+
+        let mut first = gen(ExhaustionStrategy::IncreaseLengthDeterministic);
+        let mut second = gen(ExhaustionStrategy::IncreaseLengthDeterministic);
+        let mut seen = std::collections::BTreeSet::new();
+        for _ in 0..1100 {
+            let code = first.next_vec();
+            assert_eq!(code, second.next_vec());
+            assert!(seen.insert(code));
+        }
+        assert_eq!(1, first.growth_count());
+
+        let mut plain = gen(ExhaustionStrategy::IncreaseLength);
+        for _ in 0..100 {
+            plain.next_int();
+        }
+        let result = std::panic::catch_unwind(move || plain.next_int());
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(feature = "getrandom")]
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_parallel_generators() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(1);
+        let mut par_gens = gen.clone().into_partitioned_generators(7);
+
+        for _ in 0..10000 {
+            for par_gen in &mut par_gens {
+                let expected = gen.next_string();
+                let actual = par_gen.next_string();
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_generators_cycle_mid_skip() {
+        // 10 codes split 3 ways; every partition crosses the end of the cycle
+        // in the middle of a skip at some point.
+        let mut gen =
+            ShortCodeGenerator::new_numeric(1).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let mut par_gens = gen.clone().into_partitioned_generators(3);
+
+        for _ in 0..100 {
+            for par_gen in &mut par_gens {
+                assert_eq!(gen.next_int(), par_gen.next_int());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_generators_increase_length_mid_skip() {
+        let mut gen = ShortCodeGenerator::new_numeric(1);
+        let mut par_gens = gen.clone().into_partitioned_generators(3);
+
+        for _ in 0..50 {
+            for par_gen in &mut par_gens {
+                assert_eq!(gen.next_string(), par_gen.next_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_generators_panic_mid_skip() {
+        let gen = ShortCodeGenerator::new_numeric(1).exhaustion_strategy(ExhaustionStrategy::Panic);
+        let par_gens = gen.into_partitioned_generators(3);
+
+        // Partition `i` owns the values `i, i + 3, ...` below 10.
+        for (i, mut par_gen) in par_gens.into_iter().enumerate() {
+            let owned = (i..10).step_by(3).count();
+            for _ in 0..owned {
+                par_gen.next_int();
+            }
+
+            let result = std::panic::catch_unwind(move || par_gen.next_int());
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_join_partition() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let mut partitions = gen.clone().into_partitioned_generators(3);
+
+        for _ in 0..10 {
+            partitions[1].next_vec();
+        }
+        let global_position = partitions[1].position();
+
+        for index in 0..3 {
+            let mut joined = gen
+                .clone()
+                .join_partition(index, 3, 0, global_position)
+                .unwrap();
+            let expected = (global_position..gen.capacity()).filter(|i| i % 3 == index as u64);
+            for i in expected {
+                assert_eq!(gen.code_at(i), joined.next_vec());
+            }
+        }
+
+        // A joined partition continues exactly like the running one.
+        let mut joined = gen.join_partition(1, 3, 0, global_position).unwrap();
+        for _ in 0..100 {
+            assert_eq!(partitions[1].next_vec(), joined.next_vec());
+        }
+    }
+
+    #[test]
+    fn test_join_partition_after_codes() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        for _ in 0..5 {
+            gen.next_vec();
+        }
+        let base_position = gen.position();
+        let mut partitions = gen.clone().into_partitioned_generators(3);
+
+        for partition in &mut partitions {
+            for _ in 0..10 {
+                partition.next_vec();
+            }
+        }
+        let global_position = partitions[2].position();
+
+        // Each joiner continues exactly like the running partition with its
+        // index, so it doesn't duplicate another partition's codes.
+        for (index, partition) in partitions.iter_mut().enumerate() {
+            let mut joined = gen
+                .clone()
+                .join_partition(index as u32, 3, base_position, global_position)
+                .unwrap();
+            for _ in 0..100 {
+                assert_eq!(partition.next_vec(), joined.next_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn test_join_partition_errors() {
+        let gen = ShortCodeGenerator::new_numeric(2);
+
+        assert_eq!(
+            Some(ParallelError::ZeroPartitions),
+            gen.clone().join_partition(0, 0, 0, 0).err()
+        );
+        assert_eq!(
+            Some(ParallelError::IndexOutOfRange { index: 3, total: 3 }),
+            gen.clone().join_partition(3, 3, 0, 0).err()
+        );
+        assert_eq!(
+            Some(ParallelError::PositionOutOfRange {
+                position: 101,
+                capacity: 100
+            }),
+            gen.clone().join_partition(0, 3, 0, 101).err()
+        );
+
+        assert_eq!(
+            Some(ParallelError::ExceedsCapacity {
+                partitions: 101,
+                capacity: 100
+            }),
+            gen.clone().join_partition(0, 101, 0, 0).err()
+        );
+
+        let partition = gen.into_partitioned_generators(2).remove(0);
+        assert_eq!(
+            Some(ParallelError::AlreadyParallel),
+            partition.join_partition(0, 2, 0, 0).err()
+        );
+    }
+
+    #[test]
+    fn test_period() {
+        for gen in [
+            ShortCodeGenerator::new_numeric(5),
+            ShortCodeGenerator::new_lowercase_alphanumeric(8),
+            ShortCodeGenerator::new_alphanumeric(10),
+            ShortCodeGenerator::with_alphabet("abcdefg".chars().collect(), 1),
+        ] {
+            assert_eq!(gen.capacity(), gen.period());
+        }
+    }
+
+    #[test]
+    fn test_next_distinct_prefix() {
+        let mut gen = ShortCodeGenerator::new_numeric(4);
+        let mut codes = HashSet::new();
+        let mut previous = gen.next_vec();
+        codes.insert(previous.clone());
+
+        for _ in 0..1000 {
+            let code = gen.next_distinct_prefix(2);
+            assert_ne!(previous[..2], code[..2]);
+            assert!(codes.insert(code.clone()));
+            previous = code;
+        }
+
+        // The first code has nothing to differ from.
+        let mut fresh = ShortCodeGenerator::new_numeric(4);
+        let mut reference = fresh.clone();
+        assert_eq!(reference.next_vec(), fresh.next_distinct_prefix(2));
+
+        // Partitions compare against their own previous code.
+        let mut partition = ShortCodeGenerator::new_numeric(3)
+            .into_partitioned_generators(3)
+            .remove(1);
+        let mut previous = partition.next_vec();
+        for _ in 0..100 {
+            let code = partition.next_distinct_prefix(1);
+            assert_ne!(previous[0], code[0]);
+            previous = code;
+        }
+
+        // A single code can't differ from itself, so the search gives up.
+        let mut single = ShortCodeGenerator::with_alphabet(vec!['a', 'b'], 1)
+            .exhaustion_strategy(ExhaustionStrategy::Cycle)
+            .into_partitioned_generators(2)
+            .remove(0);
+        let code = single.next_vec();
+        assert_eq!(code, single.next_distinct_prefix(1));
+
+        // The prefix is taken from the rendered code, after the marker and
+        // padding.
+        let mut marked = ShortCodeGenerator::new_numeric(3)
+            .with_display_width(4)
+            .with_partition_marker(1, 2);
+        let mut previous = marked.next_vec();
+        for _ in 0..100 {
+            let code = marked.next_distinct_prefix(3);
+            assert_eq!(['1', '0'], code[..2]);
+            assert_ne!(previous[2], code[2]);
+            previous = code;
+        }
+        let result = std::panic::catch_unwind(move || marked.next_distinct_prefix(2));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_serialized_size_hint() {
+        let numeric = ShortCodeGenerator::new_numeric(6);
+        let alphanumeric = ShortCodeGenerator::new_alphanumeric(6);
+        assert_eq!(
+            (62 - 10) * 4,
+            alphanumeric.serialized_size_hint() - numeric.serialized_size_hint()
+        );
+        assert!((200..400).contains(&numeric.serialized_size_hint()));
+
+        let constrained = numeric.clone().with_position_constraint(0, vec!['1', '2']);
+        assert_eq!(
+            numeric.serialized_size_hint() + 4 + 8 + 10 + 1,
+            constrained.serialized_size_hint()
+        );
+
+        // Codes don't change the size of the state, but each growth records
+        // the previous sequence.
+        let mut grown = ShortCodeGenerator::new_numeric(2);
+        let size = grown.serialized_size_hint();
+        for _ in 0..100 {
+            grown.next_int();
+        }
+        assert_eq!(size, grown.serialized_size_hint());
+        grown.next_int();
+        assert_eq!(size + 4 + 41 + 8, grown.serialized_size_hint());
+    }
+
+    #[test]
+    fn test_warn_then_panic() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static WARNINGS: AtomicU64 = AtomicU64::new(0);
+        static REMAINING: AtomicU64 = AtomicU64::new(u64::MAX);
+
+        let mut gen = ShortCodeGenerator::new_numeric(2)
+            .exhaustion_strategy(ExhaustionStrategy::WarnThenPanic)
+            .with_exhaustion_warning(|remaining| {
+                WARNINGS.fetch_add(1, Ordering::SeqCst);
+                REMAINING.store(remaining, Ordering::SeqCst);
+            });
+
+        for _ in 0..98 {
+            gen.next_int();
+            assert!(!gen.is_near_exhaustion(0.99));
+        }
+        assert_eq!(0, WARNINGS.load(Ordering::SeqCst));
+
+        gen.next_int();
+        assert!(gen.is_near_exhaustion(0.99));
+        assert_eq!(1, WARNINGS.load(Ordering::SeqCst));
+        assert_eq!(1, REMAINING.load(Ordering::SeqCst));
+
+        gen.next_int();
+        assert_eq!(1, WARNINGS.load(Ordering::SeqCst));
+        assert_eq!(2, gen.length);
+
+        let result = std::panic::catch_unwind(move || gen.next_int());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_new_unicode() {
+        let alphabet: Vec<char> = "山川田木水火土金日月".chars().collect();
+        let mut gen = ShortCodeGenerator::new_unicode(&alphabet, 3);
+        let code = gen.next_string();
+        assert_eq!(3, code.chars().count());
+        assert!(code.chars().all(|c| alphabet.contains(&c)));
+
+        for (alphabet, symbol) in [
+            (['a', '\u{301}', 'b'], '\u{301}'),
+            (['か', '\u{3099}', 'き'], '\u{3099}'),
+            (['a', '\u{200D}', 'b'], '\u{200D}'),
+            (['a', 'b', '\u{FE0F}'], '\u{FE0F}'),
+        ] {
+            let err = std::panic::catch_unwind(|| ShortCodeGenerator::new_unicode(&alphabet, 2))
+                .unwrap_err();
+            assert_eq!(
+                Some(&AlphabetError::CombiningSymbol { symbol }.to_string()),
+                err.downcast_ref::<String>()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn test_new_unicode_duplicate() {
+        ShortCodeGenerator::new_unicode(&['山', '川', '山'], 2);
+    }
+
+    #[test]
+    fn test_in_space() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        let capacity = gen.capacity();
+
+        assert!(gen.in_space(0));
+        assert!(gen.in_space(capacity - 1));
+        assert!(!gen.in_space(capacity));
+        assert!(!gen.in_space(u64::MAX));
+
+        assert_eq!(Some(vec!['9', '9', '9']), gen.encode_int(capacity - 1));
+        assert_eq!(None, gen.encode_int(capacity));
+
+        for _ in 0..100 {
+            let mut reference = gen.clone();
+            let value = reference.next_int();
+            assert_eq!(gen.encode_int(value), Some(gen.next_vec()));
+        }
+    }
+
+    #[test]
+    fn test_lookahead() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+
+        for n in [0, 1, 17, 999, 1000, 1001, 2500] {
+            let mut stepped = gen.clone();
+            for _ in 0..n {
+                stepped.next_int();
+            }
+
+            let mut ahead = gen.lookahead(n);
+            assert_eq!(stepped.generated_count(), ahead.generated_count());
+            assert_eq!(
+                gen.clone().skip_ahead(n).next_vec(),
+                ahead.clone().next_vec()
+            );
+            assert_eq!(stepped.next_vec(), ahead.next_vec());
+        }
+
+        let mut partitions = gen.clone().into_partitioned_generators(3);
+        let mut blocks = gen.disjoint_partitions(3);
+        for n in [1, 5, 300, 333, 400] {
+            for partition in partitions.iter_mut().chain(blocks.iter_mut()) {
+                let mut stepped = partition.clone();
+                for _ in 0..n {
+                    stepped.next_int();
+                }
+                let mut ahead = partition.lookahead(n);
+                assert_eq!(stepped.next_vec(), ahead.next_vec());
+
+                partition.next_int();
+            }
+        }
+
+        let mut constrained = gen.with_position_constraint(1, vec!['3', '4']);
+        let mut stepped = constrained.clone();
+        for _ in 0..50 {
+            stepped.next_int();
+        }
+        assert_eq!(stepped.next_vec(), constrained.skip_ahead(50).next_vec());
+    }
+
+    #[test]
+    fn test_output_whitening() {
+        let plain = ShortCodeGenerator::new_numeric(3);
+        let mut gen = plain.clone().with_output_whitening();
+
+        let codes: Vec<Vec<char>> = (0..1000).map(|_| gen.next_vec()).collect();
+        let distinct: HashSet<&Vec<char>> = codes.iter().collect();
+        assert_eq!(1000, distinct.len());
+        assert_ne!(plain.clone().next_n(10), codes[..10]);
+
+        for (index, code) in codes.iter().enumerate() {
+            assert_eq!(Some(index as u64), gen.decode_vec(code));
+            assert_eq!(*code, gen.code_at(index as u64));
+        }
+
+        // Whitening is kept through a length increase and serialization.
+        assert_eq!(4, gen.next_vec().len());
+        let json = serde_json::to_string(&gen).unwrap();
+        let mut restored: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(gen.position(), restored.position());
+        for _ in 0..100 {
+            assert_eq!(gen.next_vec(), restored.next_vec());
+        }
+
+        assert_ne!(
+            plain.config_fingerprint(),
+            plain.clone().with_output_whitening().config_fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_schema() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(4).with_output_whitening();
+        gen.next_n(10);
+
+        let json = serde_json::to_string(&gen.schema()).unwrap();
+        let schema: CodeSchema<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(4, schema.length());
+        assert_eq!(36, schema.alphabet().len());
+
+        let mut restored = ShortCodeGenerator::restore(schema.clone(), gen.position()).unwrap();
+        assert_eq!(gen.next_n(10), restored.next_n(10));
+
+        let mut other = ShortCodeGenerator::restore(schema.clone(), 1000).unwrap();
+        assert_eq!(gen.code_at(1000), other.next_vec());
+
+        let mut bad = schema;
+        bad.m += 1;
+        assert!(ShortCodeGenerator::restore(bad, 0).is_err());
+    }
+
+    #[test]
+    fn test_integer_source() {
+        for source in [
+            IntegerSource::LcmScrambled,
+            IntegerSource::Sequential,
+            IntegerSource::Halton,
+        ] {
+            let mut gen = ShortCodeGenerator::with_alphabet("01234".chars().collect(), 3)
+                .integer_source(source);
+
+            let codes: Vec<Vec<char>> = (0..125).map(|_| gen.next_vec()).collect();
+            let distinct: HashSet<&Vec<char>> = codes.iter().collect();
+            assert_eq!(125, distinct.len());
+
+            for (index, code) in codes.iter().enumerate() {
+                assert_eq!(Some(index as u64), gen.decode_vec(code));
+            }
+
+            // The source is kept through a length increase.
+            let longer: HashSet<Vec<char>> = (0..625).map(|_| gen.next_vec()).collect();
+            assert_eq!(625, longer.len());
+        }
+
+        let mut sequential =
+            ShortCodeGenerator::new_numeric(3).integer_source(IntegerSource::Sequential);
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            (0..4).map(|_| sequential.next_int()).collect::<Vec<_>>()
+        );
+
+        let mut halton = ShortCodeGenerator::new_numeric(3).integer_source(IntegerSource::Halton);
+        let mut reference = HaltonGenerator::with_alphabet("0123456789".chars().collect(), 3);
+        for _ in 0..1000 {
+            assert_eq!(reference.next_int(), halton.next_int());
+        }
+
+        let plain = ShortCodeGenerator::new_numeric(3);
+        assert_ne!(
+            plain
+                .clone()
+                .integer_source(IntegerSource::Sequential)
+                .config_fingerprint(),
+            plain
+                .clone()
+                .integer_source(IntegerSource::Halton)
+                .config_fingerprint()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "after codes have been generated")]
+    fn test_integer_source_after_generating() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        gen.next_int();
+        let _ = gen.integer_source(IntegerSource::Sequential);
+    }
+
+    #[test]
+    fn test_from_symbols() {
+        let rng = || ChaCha12Rng::from_seed([3; 32]);
+
+        let mut gen = ShortCodeGenerator::from_symbols('a'..='f', 3, rng()).unwrap();
+        let mut expected =
+            ShortCodeGenerator::with_alphabet_and_rng("abcdef".chars().collect(), 3, rng());
+        assert_eq!(216, gen.capacity());
+        assert_eq!(expected.next_vec(), gen.next_vec());
+
+        let chained = ('0'..='9')
+            .chain('a'..='z')
+            .filter(|c| !"01lo".contains(*c));
+        let gen = ShortCodeGenerator::from_symbols(chained, 4, rng()).unwrap();
+        assert_eq!(32u64.pow(4), gen.capacity());
+
+        let gen = ShortCodeGenerator::from_symbols([1u8, 2, 1, 3, 2], 2, rng()).unwrap();
+        assert_eq!(9, gen.capacity());
+
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            ShortCodeGenerator::<char>::from_symbols(std::iter::empty(), 2, rng()).err()
+        );
+        assert_eq!(
+            Some(AlphabetError::SingleSymbol),
+            ShortCodeGenerator::from_symbols("aaa".chars(), 2, rng()).err()
+        );
+    }
+
+    #[test]
+    fn test_diverges_from() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        assert_eq!(None, gen.diverges_from(&gen.clone(), 2000));
+
+        let partitions = gen.clone().into_partitioned_generators(2);
+        assert_eq!(Some(0), partitions[0].diverges_from(&partitions[1], 10));
+
+        let mut ahead = gen.clone();
+        for _ in 0..5 {
+            ahead.next_int();
+        }
+        assert_eq!(Some(0), gen.diverges_from(&ahead, 10));
+        assert_eq!(None, gen.diverges_from(&ahead, 0));
+
+        // Generators that only differ in exhaustion strategy diverge once
+        // the sequence is used up.
+        let cycling = gen.clone().exhaustion_strategy(ExhaustionStrategy::Cycle);
+        assert_eq!(Some(1000), gen.diverges_from(&cycling, 2000));
+    }
+
+    #[test]
+    fn test_partition_marker() {
+        let gen = ShortCodeGenerator::new_numeric(4);
+        let plain = gen.clone().into_partitioned_generators(3);
+        let mut marked: Vec<_> = gen
+            .clone()
+            .into_partitioned_generators(3)
+            .into_iter()
+            .enumerate()
+            .map(|(i, partition)| partition.with_partition_marker(i as u32, 3))
+            .collect();
+
+        for (i, (mut plain, marked)) in plain.into_iter().zip(&mut marked).enumerate() {
+            for _ in 0..50 {
+                let code = marked.next_vec();
+                assert_eq!(5, code.len());
+                assert_eq!(char::from_digit(i as u32, 10), Some(code[0]));
+                assert_eq!(plain.next_vec(), code[1..]);
+
+                assert_eq!(Some(i as u32), marked.partition_of(&code, 3));
+                assert_eq!(Some(i as u32), gen.partition_of(&code[1..], 3));
+                assert_eq!(
+                    Some(i as u32),
+                    marked
+                        .validate_full(&code.iter().collect::<String>(), 3)
+                        .partition
+                );
+            }
+        }
+
+        let mut with_all = ShortCodeGenerator::new_numeric(4)
+            .with_partition_marker(0, 1)
+            .with_epoch(1)
+            .with_check_symbol();
+        let code = with_all.next_string();
+        assert_eq!(7, code.len());
+        assert!(code.starts_with("00"));
+        assert!(with_all.validate_full(&code, 1).is_valid());
+    }
+
+    #[test]
+    #[should_panic(expected = "Generator is one of 3 partitions, not 2.")]
+    fn test_partition_marker_mismatch() {
+        ShortCodeGenerator::new_numeric(4)
+            .into_partitioned_generators(3)
+            .remove(0)
+            .with_partition_marker(0, 2);
+    }
+
+    #[test]
+    fn test_reserve_block() {
+        let config = ShortCodeGenerator::new_numeric(3);
+        let mut parent = config.clone();
+        let mut reference = config.clone();
+
+        for _ in 0..10 {
+            assert_eq!(reference.next_vec(), parent.next_vec());
+        }
+
+        let reservation = parent.reserve_block(100).unwrap();
+        assert_eq!(
+            BlockReservation {
+                start: 10,
+                count: 100,
+                length: 3
+            },
+            reservation
+        );
+
+        let mut child = ShortCodeGenerator::from_block(&config, reservation);
+        for _ in 0..100 {
+            assert_eq!(reference.next_vec(), child.next_vec());
+        }
+        assert_eq!(0, child.remaining());
+        assert_eq!(reference.next_vec(), parent.next_vec());
+
+        // A block can be split further.
+        let block_config = ShortCodeGenerator::from_block(&config, reservation);
+        let mut grandchild_parent = block_config.clone();
+        let sub = grandchild_parent.reserve_block(30).unwrap();
+        assert_eq!(10, sub.start);
+        assert_eq!(70, grandchild_parent.remaining());
+        assert_eq!(
+            Err(ParallelError::InsufficientCapacity {
+                requested: 71,
+                remaining: 70
+            }),
+            grandchild_parent.reserve_block(71)
+        );
+
+        let mut grandchild = ShortCodeGenerator::from_block(&block_config, sub);
+        let mut block_reference = ShortCodeGenerator::from_block(&config, reservation);
+        for _ in 0..30 {
+            assert_eq!(block_reference.next_vec(), grandchild.next_vec());
+        }
+        assert_eq!(0, grandchild.remaining());
+        assert_eq!(block_reference.next_vec(), grandchild_parent.next_vec());
+
+        let outside = parent.reserve_block(10).unwrap();
+        let result =
+            std::panic::catch_unwind(|| ShortCodeGenerator::from_block(&block_config, outside));
+        assert!(result.is_err());
+
+        assert_eq!(Err(ParallelError::EmptyBlock), parent.reserve_block(0));
+        assert_eq!(
+            Err(ParallelError::InsufficientCapacity {
+                requested: 1000,
+                remaining: 879
+            }),
+            parent.reserve_block(1000)
+        );
+        let mut partition = config.clone().into_partitioned_generators(2).remove(0);
+        assert_eq!(
+            Err(ParallelError::AlreadyParallel),
+            partition.reserve_block(1)
+        );
+
+        let result = std::panic::catch_unwind(move || child.next_vec());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_format_index() {
+        let gen = ShortCodeGenerator::new_lowercase_alphanumeric(5);
+        let code: String = gen.code_at(7).into_iter().collect();
+
+        assert_eq!(
+            Some(code.clone()),
+            gen.format_index(7, FormatOptions::default())
+        );
+
+        let opts = FormatOptions {
+            prefix: "ID-".to_string(),
+            group_size: 3,
+            separator: '-',
+            checksum: true,
+        };
+        let formatted = gen.format_index(7, opts.clone()).unwrap();
+        assert_eq!(10, formatted.len());
+        assert!(formatted.starts_with(&format!("ID-{}-{}", &code[..3], &code[3..])));
+
+        // The check symbol matches what with_check_symbol generates.
+        let mut checked = gen.clone().with_check_symbol();
+        let opts_checksum = FormatOptions {
+            checksum: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            Some(checked.next_string()),
+            gen.format_index(0, opts_checksum)
+        );
+
+        assert_eq!(None, gen.format_index(gen.capacity(), opts));
+    }
+
+    #[test]
+    fn test_assign_sorted() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let codes = gen.assign_sorted(&[30, 10, 20, 10]);
+
+        assert_eq!(
+            vec![
+                gen.code_at(3),
+                gen.code_at(0),
+                gen.code_at(2),
+                gen.code_at(1)
+            ],
+            codes
+        );
+        assert_eq!(codes, gen.assign_sorted(&[30, 10, 20, 10]));
+
+        // Codes match the order generation would hand them out.
+        let mut fresh = gen.clone();
+        let codes = gen.assign_sorted(&[5, 4, 3, 2, 1]);
+        for code in codes.iter().rev() {
+            assert_eq!(*code, fresh.next_vec());
+        }
+
+        assert!(gen.assign_sorted(&[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't assign 11 codes from a space of 10.")]
+    fn test_assign_sorted_too_many() {
+        ShortCodeGenerator::new_numeric(1).assign_sorted(&[0; 11]);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let first = gen.next_vec();
+        let second = gen.next_vec();
+        let third = gen.next_vec();
+
+        assert_eq!(Some((first.clone(), third)), gen.neighbors(&second));
+
+        let last = gen.code_at(99);
+        assert_eq!(Some((last.clone(), second)), gen.neighbors(&first));
+        assert_eq!(first, gen.neighbors(&last).unwrap().1);
+
+        assert_eq!(None, gen.neighbors(&['1']));
+        assert_eq!(None, gen.neighbors(&['1', 'x']));
+    }
+
+    #[test]
+    fn test_default() {
+        let mut gen = ShortCodeGenerator::default();
+        assert_eq!(36u64.pow(8), gen.capacity());
+
+        let code = gen.next_string();
+        assert_eq!(8, code.len());
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase()));
+    }
+
+    #[test]
+    fn test_next_matching() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        for _ in 0..10 {
+            let code = gen.next_matching(|code| code[0] == '7').unwrap();
+            assert_eq!('7', code[0]);
+        }
+
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        gen.next_n(10);
+        assert_eq!(None, gen.next_matching(|_| false));
+        assert_eq!(0, gen.remaining());
+        assert_eq!(0, gen.growth_count());
+
+        // Partitions only scan their own share.
+        let mut partition = ShortCodeGenerator::new_numeric(2)
+            .into_partitioned_generators(4)
+            .remove(1);
+        let count = std::cell::Cell::new(0);
+        assert_eq!(
+            None,
+            partition.next_matching(|_| {
+                count.set(count.get() + 1);
+                false
+            })
+        );
+        assert_eq!(25, count.get());
+
+        // Values rejected by constraints at the end of the cycle don't
+        // trigger the exhaustion strategy.
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).with_position_constraint(0, vec!['1', '2']);
+        let mut found = Vec::new();
+        while let Some(code) = gen.next_matching(|_| true) {
+            found.push(code);
+        }
+        assert_eq!(20, found.len());
+        assert!(found.iter().all(|code| code[0] == '1' || code[0] == '2'));
+        assert_eq!(0, gen.growth_count());
+        assert_eq!(None, gen.next_matching(|_| true));
+        assert_eq!(3, gen.next_vec().len());
+    }
+
+    #[test]
+    fn test_current_strategy() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        assert!(matches!(
+            gen.current_strategy(),
+            ExhaustionStrategy::IncreaseLength
+        ));
+
+        let gen = gen.exhaustion_strategy(ExhaustionStrategy::Cycle);
+        assert!(matches!(gen.current_strategy(), ExhaustionStrategy::Cycle));
+
+        // The configured strategy is kept after the length cap is reached.
+        let mut gen = ShortCodeGenerator::new_numeric(1).with_max_length(1);
+        gen.next_n(10);
+        assert!(matches!(
+            gen.current_strategy(),
+            ExhaustionStrategy::IncreaseLength
+        ));
+    }
+
+    #[test]
+    fn test_count_with_prefix() {
+        let gen = ShortCodeGenerator::new_numeric(4);
+        assert_eq!(gen.capacity(), gen.count_with_prefix(&[]));
+        assert_eq!(1000, gen.count_with_prefix(&['7']));
+        assert_eq!(10, gen.count_with_prefix(&['1', '2', '3']));
+        assert_eq!(1, gen.count_with_prefix(&['1', '2', '3', '4']));
+        assert_eq!(0, gen.count_with_prefix(&['1', '2', '3', '4', '5']));
+        assert_eq!(0, gen.count_with_prefix(&['1', 'x']));
+
+        // Matches the codes actually generated.
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(2);
+        let count = (0..gen.capacity())
+            .filter(|_| gen.next_vec().starts_with(&['q']))
+            .count();
+        assert_eq!(gen.count_with_prefix(&['q']), count as u64);
+    }
+
+    #[test]
+    fn test_possible_indices() {
+        let mut gen = ShortCodeGenerator::new_numeric(1);
+        let short: Vec<String> = (0..10).map(|_| gen.next_string()).collect();
+        let long: Vec<String> = (0..100).map(|_| gen.next_string()).collect();
+        assert_eq!(2, gen.length);
+
+        // "07" is the 2-symbol code at some index, and "7" padded.
+        let ambiguous = long.iter().position(|code| code == "07").unwrap() as u64;
+        let padded = short.iter().position(|code| code == "7").unwrap() as u64;
+        assert_eq!(vec![ambiguous, padded], gen.possible_indices("07"));
+
+        // Codes not starting with the first symbol are unambiguous.
+        let index = long.iter().position(|code| code == "42").unwrap() as u64;
+        assert_eq!(vec![index], gen.possible_indices("42"));
+
+        // Padding across more than one length.
+        let mut gen = ShortCodeGenerator::new_numeric(1);
+        let short: Vec<String> = (0..10).map(|_| gen.next_string()).collect();
+        gen.next_n(1000);
+        assert_eq!(3, gen.length);
+        let padded = short.iter().position(|code| code == "3").unwrap() as u64;
+        let indices = gen.possible_indices("003");
+        assert_eq!(3, indices.len());
+        assert_eq!(padded, indices[2]);
+
+        assert!(gen.possible_indices("0x3").is_empty());
+        assert!(gen.possible_indices("12345").is_empty());
+    }
+
+    #[test]
+    fn test_into_string_fn() {
+        fn collect_ids(n: usize, mut make_id: impl FnMut() -> String) -> Vec<String> {
+            (0..n).map(|_| make_id()).collect()
+        }
+
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let expected = gen.clone().next_n(10);
+        let ids = collect_ids(10, gen.into_string_fn());
+        let expected: Vec<String> = expected.into_iter().map(String::from_iter).collect();
+        assert_eq!(expected, ids);
+    }
+
+    #[test]
+    fn test_consecutive_symbol_change() {
+        for (alphabet, length) in [
+            ("0123456789", 4),
+            ("01", 10),
+            ("abcdefghijklmnopqrstuvwxyz", 5),
+        ] {
+            let gen =
+                ShortCodeGenerator::with_alphabet_for_test(alphabet.chars().collect(), length);
+            let random = 1.0 - 1.0 / alphabet.len() as f64;
+            assert!((gen.consecutive_symbol_change() - random).abs() < 0.1);
+
+            let whitened = gen.with_output_whitening();
+            assert!((whitened.consecutive_symbol_change() - random).abs() < 0.1);
+        }
+
+        // A counter mostly changes only its first symbol.
+        let sequential =
+            ShortCodeGenerator::new_numeric(4).integer_source(IntegerSource::Sequential);
+        let change = sequential.consecutive_symbol_change();
+        assert!((0.27..0.28).contains(&change), "{}", change);
+    }
+
+    #[test]
+    fn test_take_n() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        let mut reference = gen.clone();
+
+        assert_eq!(reference.next_n(5), gen.take_n(5).collect::<Vec<_>>());
+
+        let partial: Vec<Vec<char>> = gen.take_n(10).take(3).collect();
+        assert_eq!(reference.next_n(3), partial);
+        assert_eq!(reference.next_vec(), gen.next_vec());
+
+        assert_eq!(0, gen.take_n(0).count());
+        assert_eq!(reference.next_vec(), gen.next_vec());
+    }
+
+    #[test]
+    fn test_try_into_partitioned_generators() {
+        let gen = ShortCodeGenerator::new_numeric(1);
+
+        let partitions = gen.clone().try_into_partitioned_generators(3).unwrap();
+        let expected = gen.clone().into_partitioned_generators(3);
+        for (mut partition, mut expected) in partitions.into_iter().zip(expected) {
+            assert_eq!(expected.next_int(), partition.next_int());
+        }
+
+        assert_eq!(
+            Some(ParallelError::ZeroPartitions),
+            gen.clone().try_into_partitioned_generators(0).err()
+        );
+        assert_eq!(
+            Some(ParallelError::ExceedsCapacity {
+                partitions: 11,
+                capacity: 10
+            }),
+            gen.clone().try_into_partitioned_generators(11).err()
+        );
+        assert_eq!(
+            10,
+            gen.clone()
+                .try_into_partitioned_generators(10)
+                .unwrap()
+                .len()
+        );
+
+        let partition = gen.clone().into_partitioned_generators(2).remove(0);
+        assert_eq!(
+            Some(ParallelError::AlreadyParallel),
+            partition.try_into_partitioned_generators(2).err()
+        );
+        let block = gen.disjoint_partitions(2).remove(0);
+        assert_eq!(
+            Some(ParallelError::AlreadyParallel),
+            block.try_into_partitioned_generators(2).err()
+        );
+    }
+
+    #[test]
+    fn test_zero_partitions() {
+        let gen = ShortCodeGenerator::new_numeric(1);
+        assert!(gen.into_partitioned_generators(0).is_empty());
+    }
+
+    #[test]
+    fn test_partitions_equal_to_capacity() {
+        let gen = ShortCodeGenerator::with_alphabet(vec!['a', 'b', 'c'], 1)
+            .exhaustion_strategy(ExhaustionStrategy::Cycle);
+
+        let mut partitions = gen.clone().into_partitioned_generators(3);
+        let codes: HashSet<Vec<char>> = partitions.iter_mut().map(|p| p.next_vec()).collect();
+        assert_eq!(3, codes.len());
+
+        // Each partition keeps generating its own code.
+        for (partition, code) in partitions.iter_mut().zip([0, 1, 2]) {
+            assert_eq!(gen.code_at(code), partition.next_vec());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't split a space of 3 codes into 5 partitions.")]
+    fn test_partitions_exceed_capacity() {
+        ShortCodeGenerator::with_alphabet(vec!['a', 'b', 'c'], 1).into_partitioned_generators(5);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_n() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(4);
+        let mut expected = gen.clone();
+
+        let mut out = Vec::new();
+        gen.write_n(100, b'\n', &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        let codes: Vec<&str> = out.split('\n').collect();
+        assert_eq!(100, codes.len());
+        for code in codes {
+            assert_eq!(expected.next_string(), code);
+        }
+
+        // Both generators are left in the same state.
+        assert_eq!(expected.next_string(), gen.next_string());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_n_zero() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(4);
+        let mut out = Vec::new();
+        gen.write_n(0, b',', &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    fn assert_disjoint_partitions(alphabet_size: u32, length: usize, total: u32) {
+        let alphabet: Vec<u32> = (0..alphabet_size).collect();
+        let gen = ShortCodeGenerator::with_alphabet(alphabet, length)
+            .exhaustion_strategy(ExhaustionStrategy::Panic);
+        let capacity = (alphabet_size as u64).pow(length as u32);
+
+        let mut seen = HashSet::new();
+        for mut partition in gen.disjoint_partitions(total) {
+            while !partition.exhausted() {
+                assert!(seen.insert(partition.next_vec()));
+            }
+        }
+
+        assert_eq!(capacity, seen.len() as u64);
+    }
+
+    #[test]
+    fn test_disjoint_partitions() {
+        assert_disjoint_partitions(10, 2, 1);
+        assert_disjoint_partitions(10, 2, 4);
+        assert_disjoint_partitions(7, 3, 13);
+        assert_disjoint_partitions(3, 1, 5);
+    }
+
+    #[test]
+    fn test_disjoint_partitions_are_contiguous() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let partitions = gen.disjoint_partitions(3);
+
+        for mut partition in partitions {
+            while !partition.exhausted() {
+                assert_eq!(gen.next_int(), partition.next_int());
+            }
+        }
+    }
+
+    #[test]
+    fn test_disjoint_partitions_cycle() {
+        let gen = ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let mut partition = gen.disjoint_partitions(3).remove(1);
+
+        let first: Vec<u64> = (0..33).map(|_| partition.next_int()).collect();
+        let second: Vec<u64> = (0..33).map(|_| partition.next_int()).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_disjoint_partitions_increase_length() {
+        let gen = ShortCodeGenerator::new_numeric(1);
+        let mut partitions = gen.disjoint_partitions(2);
+
+        // Exhaust the length-1 space, then take codes from the length-2 space.
+        let mut seen = HashSet::new();
+        for partition in &mut partitions {
+            for _ in 0..5 {
+                assert_eq!(1, partition.next_string().len());
+            }
+            for _ in 0..50 {
+                let code = partition.next_string();
+                assert_eq!(2, code.len());
+                assert!(seen.insert(code));
+            }
+        }
+    }
+
+    #[test]
+    fn test_clones_share_alphabet() {
+        let gen = ShortCodeGenerator::new_lowercase_alphanumeric(3);
+        let clone = gen.clone();
+        assert!(Arc::ptr_eq(&gen.alphabet, &clone.alphabet));
+
+        for partition in gen.clone().into_partitioned_generators(4) {
+            assert!(Arc::ptr_eq(&gen.alphabet, &partition.alphabet));
+        }
+
+        // Growing the code length keeps the same alphabet allocation.
+        let mut grown = gen.clone();
+        for _ in 0..=36usize.pow(3) {
+            grown.next_int();
+        }
+        assert_eq!(4, grown.length);
+        assert!(Arc::ptr_eq(&gen.alphabet, &grown.alphabet));
+    }
+
+    #[test]
+    fn test_unget() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(3);
+        assert!(!gen.unget());
+
+        for _ in 0..100 {
+            let value = gen.next_int();
+            assert!(gen.unget());
+            assert!(!gen.unget());
+            assert_eq!(value, gen.next_int());
+        }
+    }
+
+    #[test]
+    fn test_unget_partitioned() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let mut par_gen = gen.clone().into_partitioned_generators(3).remove(1);
+
+        gen.next_int();
+        for _ in 0..20 {
+            let expected = gen.next_int();
+            let value = par_gen.next_int();
+            assert_eq!(expected, value);
+
+            assert!(par_gen.unget());
+            assert_eq!(value, par_gen.next_int());
+
+            gen.next_int();
+            gen.next_int();
+        }
+    }
+
+    #[test]
+    fn test_unget_at_exhaustion() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(1).exhaustion_strategy(ExhaustionStrategy::Panic);
+
+        let mut last = 0;
+        for _ in 0..10 {
+            last = gen.next_int();
+        }
+
+        // Returning the last code makes it available again without panicking.
+        assert!(gen.unget());
+        assert_eq!(last, gen.next_int());
+    }
+
+    #[test]
+    fn test_utilization() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Panic);
+        assert_eq!(100, gen.capacity());
+        assert_eq!(0, gen.position());
+        assert_eq!(0.0, gen.utilization());
+
+        for i in 1..=100 {
+            gen.next_int();
+            assert_eq!(i, gen.position());
+        }
+
+        assert_eq!(1.0, gen.utilization());
+        assert!(gen.is_near_exhaustion(0.99));
+    }
+
+    #[test]
+    fn test_utilization_near_exhaustion() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        for _ in 0..995 {
+            gen.next_int();
+        }
+
+        assert!((gen.utilization() - 0.995).abs() < 1e-9);
+        assert!(gen.is_near_exhaustion(0.99));
+        assert!(!gen.is_near_exhaustion(0.999));
+    }
+
+    #[test]
+    fn test_utilization_partitioned() {
+        let gen = ShortCodeGenerator::new_numeric(1).exhaustion_strategy(ExhaustionStrategy::Panic);
+
+        // 10 codes split 3 ways: partitions own 4, 3 and 3 codes.
+        for (i, mut par_gen) in gen.into_partitioned_generators(3).into_iter().enumerate() {
+            let owned = if i == 0 { 4 } else { 3 };
+            assert_eq!(0.0, par_gen.utilization());
+
+            for j in 1..=owned {
+                par_gen.next_int();
+                assert!((par_gen.utilization() - j as f64 / owned as f64).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_range() {
+        let mut gen = ShortCodeGenerator::new_char_range('a', 'f', 2);
+        assert_eq!(36, gen.capacity());
+
+        let mut seen = HashSet::new();
+        for _ in 0..36 {
+            let code = gen.next_string();
+            assert!(code.chars().all(|c| ('a'..='f').contains(&c)));
+            seen.insert(code);
+        }
+        assert_eq!(36, seen.len());
+
+        assert_eq!(
+            1,
+            ShortCodeGenerator::new_char_range('x', 'x', 3).capacity()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_char_range_reversed() {
+        ShortCodeGenerator::new_char_range('z', 'a', 3);
+    }
+
+    #[test]
+    fn test_capacity_overflow() {
+        let binary = || vec![0u8, 1];
+
+        let gen = ShortCodeGenerator::try_with_alphabet(binary(), 63).unwrap();
+        assert_eq!(1 << 63, gen.capacity());
+
+        assert_eq!(
+            AlphabetError::Overflow {
+                alphabet_len: 2,
+                length: 64
+            },
+            ShortCodeGenerator::try_with_alphabet(binary(), 64).unwrap_err()
+        );
+        assert!(matches!(
+            ShortCodeGenerator::try_with_alphabet(binary(), 70),
+            Err(AlphabetError::Overflow { .. })
+        ));
+        assert!(matches!(
+            ShortCodeGenerator::try_with_alphabet(binary(), usize::MAX),
+            Err(AlphabetError::Overflow { .. })
+        ));
+        assert_eq!(
+            AlphabetError::Empty,
+            ShortCodeGenerator::<u8>::try_with_alphabet(vec![], 5).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_large_capacity_does_not_overflow() {
+        let mut gen = ShortCodeGenerator::try_with_alphabet(vec![0u8, 1], 63).unwrap();
+        let mut seen = HashSet::new();
+        for _ in 0..1000 {
+            let value = gen.next_int();
+            assert!(value < gen.capacity());
+            assert!(seen.insert(value));
+        }
+
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(12);
+        for _ in 0..1000 {
+            assert_eq!(12, gen.next_string().len());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows a u64")]
+    fn test_with_alphabet_overflow_panics() {
+        ShortCodeGenerator::with_alphabet(vec![0u8, 1], 64);
+    }
+
+    #[test]
+    fn test_resumable_drain() {
+        let alphabet: Vec<char> = "abcde".chars().collect();
+        let make_gen = || ShortCodeGenerator::with_alphabet_for_test(alphabet.clone(), 4);
+
+        // First run: drain part of the space and store the position.
+        let mut gen = make_gen();
+        let mut drain = gen.resumable_drain();
+        let mut seen: HashSet<Vec<char>> = drain.by_ref().take(200).collect();
+        let stored_position = drain.position();
+        assert_eq!(200, stored_position);
+
+        // Second run: reconstruct, seek and drain the rest.
+        let mut gen = make_gen();
+        gen.seek(stored_position);
+        for code in gen.resumable_drain() {
+            assert!(seen.insert(code));
+        }
+
+        assert_eq!(625, seen.len());
+        assert_eq!(625, gen.position());
+    }
+
+    #[test]
+    fn test_resumable_drain_stops_at_exhaustion() {
+        // The drain stops rather than increasing the length.
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        assert_eq!(100, gen.resumable_drain().count());
+        assert_eq!(0, gen.resumable_drain().count());
+
+        let mut par_gen = ShortCodeGenerator::new_numeric(2)
+            .into_partitioned_generators(3)
+            .remove(2);
+        assert_eq!(33, par_gen.resumable_drain().count());
+    }
+
+    #[test]
+    fn test_cycle_position_after_wrap() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(1).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        for _ in 0..10 {
+            gen.next_int();
+        }
+        assert_eq!(10, gen.position());
+
+        gen.next_int();
+        assert_eq!(1, gen.position());
+        assert_eq!(9, gen.resumable_drain().count());
+    }
+
+    #[test]
+    fn test_emoji() {
+        let mut gen = ShortCodeGenerator::new_emoji(2);
+        assert_eq!(1024, gen.capacity());
+
+        let mut seen = HashSet::new();
+        for _ in 0..1024 {
+            let symbols = gen.clone().next_vec();
+            let code = gen.next_string();
+            assert_eq!(symbols.concat(), code);
+            assert!(symbols
+                .iter()
+                .all(|s| ShortCodeGenerator::EMOJI.contains(s)));
+            assert!(seen.insert(code));
+        }
+    }
+
+    #[test]
+    fn test_str_alphabet() {
+        let mut gen = ShortCodeGenerator::with_alphabet(vec!["ab", "cd", "ef"], 3);
+        let code = gen.next_string();
+        assert_eq!(6, code.len());
+    }
+
+    #[test]
+    fn test_owned_symbols() {
+        let alphabet: Vec<String> = ["red", "green", "blue"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut gen = ShortCodeGenerator::with_alphabet(alphabet, 2);
+
+        let mut seen = HashSet::new();
+        for _ in 0..9 {
+            assert!(seen.insert(gen.next_string()));
+        }
+
+        // Owned symbols survive a serde round trip.
+        let json = serde_json::to_string(&gen).unwrap();
+        let mut restored: ShortCodeGenerator<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(gen.next_vec(), restored.next_vec());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-deprecated"))]
+    #[allow(deprecated)]
+    fn test_deprecated_next_after_deserialize() {
+        let mut fresh = ShortCodeGenerator::new_numeric(2);
+        let mut used = fresh.clone();
+        for _ in 0..150 {
+            used.next_int();
+        }
+        let partitions = fresh.clone().into_partitioned_generators(3);
+
+        let mut generators = vec![fresh.clone(), used];
+        generators.extend(partitions);
+
+        for mut gen in generators {
+            let json = serde_json::to_string(&gen).unwrap();
+            let mut restored: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+
+            for _ in 0..300 {
+                assert_eq!(gen.next_vec(), restored.next());
+            }
+            assert_eq!(gen.generated_count(), restored.generated_count());
+            assert_eq!(
+                serde_json::to_string(&gen).unwrap(),
+                serde_json::to_string(&restored).unwrap()
+            );
+        }
+
+        // State saved before any code was generated behaves like the fresh
+        // generator it came from.
+        let json = serde_json::to_string(&fresh).unwrap();
+        let mut restored: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(fresh.next_vec(), restored.next());
+    }
+
+    #[test]
+    fn test_growth_count() {
+        let mut gen = ShortCodeGenerator::new_numeric(1);
+        assert_eq!(0, gen.growth_count());
+
+        for _ in 0..10 {
+            gen.next_int();
+        }
+        assert_eq!(0, gen.growth_count());
+
+        gen.next_int();
+        assert_eq!(1, gen.growth_count());
+
+        for _ in 0..100 {
+            gen.next_int();
+        }
+        assert_eq!(2, gen.growth_count());
+
+        let json = serde_json::to_string(&gen).unwrap();
+        let restored: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(2, restored.growth_count());
+
+        // Generators serialized before the count existed start from zero.
+        let mut legacy: ShortCodeGenerator<char> = serde_json::from_str(
+            r#"{
+                "lcm": {"first": 1, "next": 1, "m": 64, "c": 1, "a": 5, "exhausted": false},
+                "offset": 16,
+                "alphabet": ["g", "h", "i", "j"],
+                "length": 3,
+                "exhaustion_strategy": "IncreaseLength",
+                "rng": {"seed": [
+                    50, 32, 156, 125, 71, 52, 54, 124, 10, 5, 100, 142, 252, 16, 120, 159,
+                    27, 204, 74, 211, 3, 75, 160, 85, 87, 13, 117, 73, 214, 197, 115, 217
+                ], "stream": 0, "word_pos": 6},
+                "skip": null,
+                "used": false
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(0, legacy.growth_count());
+
+        // 4^3 + 4^4 + 4^5 + 4^6 < 11103 < 4^3 + ... + 4^7.
+        for _ in 0..11103 {
+            legacy.next_int();
+        }
+        assert_eq!(4, legacy.growth_count());
+    }
+
+    #[test]
+    fn test_min_length_for() {
+        assert_eq!(Ok(1), min_length_for(10, 0));
+        assert_eq!(Ok(1), min_length_for(10, 10));
+        assert_eq!(Ok(2), min_length_for(10, 11));
+        assert_eq!(Ok(2), min_length_for(10, 100));
+        assert_eq!(Ok(3), min_length_for(10, 101));
+        assert_eq!(Ok(63), min_length_for(2, 1 << 63));
+        assert_eq!(
+            Err(AlphabetError::Overflow {
+                alphabet_len: 2,
+                length: 64
+            }),
+            min_length_for(2, (1 << 63) + 1)
+        );
+        assert_eq!(Ok(1), min_length_for(1, 1));
+        assert_eq!(Err(AlphabetError::SingleSymbol), min_length_for(1, 2));
+        assert_eq!(Err(AlphabetError::Empty), min_length_for(0, 2));
+    }
+
+    #[test]
+    fn test_merge_alphabets() {
+        let digits: Vec<char> = ('0'..='9').collect();
+        let curated: Vec<char> = "abc123xyz".chars().collect();
+        let merged = merge_alphabets(&[&digits, &curated]);
+        assert_eq!("0123456789abcxyz", merged.iter().collect::<String>());
+
+        // Duplicates within a part are removed too.
+        assert_eq!(vec![3, 1, 2], merge_alphabets(&[&[3, 1, 3], &[2, 1], &[]]));
+        assert!(merge_alphabets::<u8>(&[]).is_empty());
+
+        let gen = ShortCodeGenerator::with_alphabet(merged, 2);
+        assert_eq!(256, gen.capacity());
+    }
+
+    #[test]
+    fn test_fleet_capacity() {
+        assert_eq!(Some(1000), fleet_capacity(10, 3, 1));
+        assert_eq!(Some(1000), fleet_capacity(10, 3, 8));
+        assert_eq!(Some(1000), fleet_capacity(10, 3, 1000));
+        assert_eq!(None, fleet_capacity(10, 3, 1001));
+        assert_eq!(None, fleet_capacity(10, 3, 0));
+        assert_eq!(None, fleet_capacity(2, 64, 1));
+
+        // The partitions together issue each code of the space once.
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let codes: HashSet<Vec<char>> = gen
+            .into_partitioned_generators(8)
+            .into_iter()
+            .flat_map(|mut partition| partition.resumable_drain().collect::<Vec<_>>())
+            .collect();
+        assert_eq!(fleet_capacity(10, 3, 8), Some(codes.len() as u64));
+    }
+
+    #[test]
+    fn test_build() {
+        let mut gen = ShortCodeGenerator::build(
+            Alphabet(vec!['a', 'b']),
+            Length(3),
+            ChaCha12Rng::seed_from_u64(5),
+        )
+        .unwrap();
+        let mut reference = ShortCodeGenerator::with_alphabet_and_rng(
+            vec!['a', 'b'],
+            3,
+            ChaCha12Rng::seed_from_u64(5),
+        );
+
+        for _ in 0..8 {
+            assert_eq!(reference.next_string(), gen.next_string());
+        }
+
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            ShortCodeGenerator::<char>::build(
+                Alphabet(vec![]),
+                Length(3),
+                ChaCha12Rng::seed_from_u64(5)
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_with_alphabet_and_seed_bytes() {
+        let alphabet: Vec<char> = "0123456789".chars().collect();
+        let mut a = ShortCodeGenerator::with_alphabet_and_seed_bytes(alphabet.clone(), 4, [3; 32]);
+        let mut b = ShortCodeGenerator::with_alphabet_and_seed_bytes(alphabet.clone(), 4, [3; 32]);
+        let mut c = ShortCodeGenerator::with_alphabet_and_seed_bytes(alphabet, 4, [4; 32]);
+
+        let codes: Vec<String> = (0..100).map(|_| a.next_string()).collect();
+        assert_eq!(codes, (0..100).map(|_| b.next_string()).collect::<Vec<_>>());
+        assert_ne!(codes, (0..100).map(|_| c.next_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_with_alphabet_for_test() {
+        let alphabet: Vec<char> = "0123456789".chars().collect();
+        let mut a = ShortCodeGenerator::with_alphabet_for_test(alphabet.clone(), 4);
+        let mut b = ShortCodeGenerator::with_alphabet_for_test(alphabet, 4);
+        assert_eq!(a.next_n(100), b.next_n(100));
+
+        // Growth is reproducible too.
+        let mut a = ShortCodeGenerator::with_alphabet_for_test(vec![0u8, 1], 2);
+        let mut b = ShortCodeGenerator::with_alphabet_for_test(vec![0u8, 1], 2);
+        assert_eq!(a.next_n(20), b.next_n(20));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        type Gen = ShortCodeGenerator<char>;
+
+        assert_eq!(Ok(()), Gen::validate_config(36, 5));
+        assert_eq!(Ok(()), Gen::validate_config(2, 63));
+        assert_eq!(Err(AlphabetError::Empty), Gen::validate_config(0, 5));
+        assert_eq!(Err(AlphabetError::SingleSymbol), Gen::validate_config(1, 5));
+        assert_eq!(
+            Err(AlphabetError::Overflow {
+                alphabet_len: 2,
+                length: 64
+            }),
+            Gen::validate_config(2, 64)
+        );
+    }
+
+    #[test]
+    fn test_full_period_alphabet_sizes() {
+        for alphabet_size in 2..=64u32 {
+            let alphabet: Vec<u32> = (0..alphabet_size).collect();
+
+            for length in 1..=4 {
+                let gen = ShortCodeGenerator::try_with_alphabet_and_rng(
+                    alphabet.clone(),
+                    length,
+                    ChaCha12Rng::seed_from_u64(alphabet_size as u64),
+                )
+                .unwrap();
+                assert!(gen.verify_full_period(), "{} ^ {}", alphabet_size, length);
+
+                if gen.capacity() <= 5000 {
+                    let mut gen = gen;
+                    let seen: HashSet<u64> = (0..gen.capacity()).map(|_| gen.next_int()).collect();
+                    assert_eq!(gen.capacity(), seen.len() as u64);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_full_period_rejects_bad_state() {
+        let mut state = serde_json::to_value(ShortCodeGenerator::new_numeric(2)).unwrap();
+        state["lcm"]["a"] = serde_json::json!(3);
+
+        let gen: ShortCodeGenerator<char> = serde_json::from_value(state).unwrap();
+        assert!(!gen.verify_full_period());
+    }
+
+    #[test]
+    fn test_for_capacity() {
+        let alphabet: Vec<char> = "0123456789".chars().collect();
+        let rng = || ChaCha12Rng::seed_from_u64(0);
+
+        let gen = ShortCodeGenerator::for_capacity(alphabet.clone(), 1000, rng()).unwrap();
+        assert_eq!(1000, gen.capacity());
+
+        let mut gen = ShortCodeGenerator::for_capacity(alphabet.clone(), 1001, rng()).unwrap();
+        assert_eq!(10000, gen.capacity());
+        assert_eq!(4, gen.next_string().len());
+
+        assert!(matches!(
+            ShortCodeGenerator::for_capacity(alphabet, u64::MAX, rng()),
+            Err(AlphabetError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_exhaustion_increase_length_padded() {
+        let mut gen = ShortCodeGenerator::new_numeric(1)
+            .exhaustion_strategy(ExhaustionStrategy::IncreaseLengthPadded);
+
+        // Codes as they'd be stored in a field of width 3.
+        let mut seen = HashSet::new();
+        let pad = |code: String| format!("{:0>3}", code);
+
+        for _ in 0..10 {
+            let code = gen.next_string();
+            assert_eq!(1, code.len());
+            assert!(seen.insert(pad(code)));
+        }
+
+        // 90 of the 100 two-symbol codes don't start with '0'.
+        for _ in 0..90 {
+            let code = gen.next_string();
+            assert_eq!(2, code.len());
+            assert!(!code.starts_with('0'));
+            assert!(seen.insert(pad(code)));
+        }
+
+        for _ in 0..900 {
+            let code = gen.next_string();
+            assert_eq!(3, code.len());
+            assert!(seen.insert(code));
+        }
+
+        assert_eq!(1000, seen.len());
+        assert_eq!(4, gen.next_string().len());
+    }
+
+    #[test]
+    fn test_decode_vec() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(3);
+        let reference = gen.clone();
+
+        for i in 0..2000 {
+            let code = gen.next_vec();
+            assert_eq!(Some(i), reference.decode_vec(&code));
+        }
+
+        assert_eq!(None, reference.decode_vec(&['a', 'b']));
+        assert_eq!(None, reference.decode_vec(&['a', 'b', '!']));
+    }
+
+    #[test]
+    fn test_decode_many() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(3);
+        let reference = gen.clone();
+
+        let mut codes = gen.next_n(1000);
+        codes.push(vec!['!', 'a', 'b']);
+
+        let indices = reference.decode_many(&codes);
+        assert_eq!(1001, indices.len());
+        for (i, index) in indices[..1000].iter().enumerate() {
+            assert_eq!(Some(i as u64), *index);
+        }
+        assert_eq!(None, indices[1000]);
+    }
+
+    #[test]
+    fn test_next_with_index() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(2);
+
+        for _ in 0..2000 {
+            let (code, index) = gen.next_with_index();
+            assert_eq!(Some(index), gen.decode_vec(&code));
+        }
+    }
+
+    #[test]
+    fn test_partition_of() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let partitions = gen.clone().into_partitioned_generators(4);
+
+        for (i, mut partition) in partitions.into_iter().enumerate() {
+            for _ in 0..100 {
+                let code = partition.next_vec();
+                assert_eq!(Some(i as u32), gen.partition_of(&code, 4));
+                assert_eq!(Some(i as u32), partition.partition_of(&code, 4));
+            }
+        }
+
+        assert_eq!(None, gen.partition_of(&['1', '2'], 4));
+        assert_eq!(None, gen.partition_of(&['1', '2', '3'], 0));
+    }
+
+    #[test]
+    fn test_cycle_start() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let start = gen.cycle_start();
+
+        assert_eq!(start, gen.next_vec());
+        for _ in 0..99 {
+            assert_eq!(start, gen.cycle_start());
+            gen.next_vec();
+        }
+
+        // The cycle wraps around to its start.
+        assert_eq!(start, gen.next_vec());
+    }
+
+    #[test]
+    fn test_cycle_start_is_first_code() {
+        for (alphabet_size, length) in [(3, 3), (7, 3), (4, 9), (26, 4), (44, 3), (7, 5)] {
+            let alphabet: Vec<u32> = (0..alphabet_size).collect();
+            let mut gen = ShortCodeGenerator::with_alphabet(alphabet, length)
+                .exhaustion_strategy(ExhaustionStrategy::Cycle);
+            let start = gen.cycle_start();
+
+            assert_eq!(start, gen.next_vec());
+            assert_eq!(start, gen.cycle_start());
+        }
+    }
+
+    #[test]
+    fn test_enumerate_codes() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let reference = gen.clone();
+
+        for (expected, (index, code)) in gen.enumerate_codes().take(100).enumerate() {
+            assert_eq!(expected as u64, index);
+            assert_eq!(reference.code_at(index), code);
+        }
+
+        // Indices restart once the length increases.
+        let (index, code) = gen.enumerate_codes().next().unwrap();
+        assert_eq!(0, index);
+        assert_eq!(gen.code_at(index), code);
+    }
+
+    #[test]
+    fn test_enumerate_codes_partitioned() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let reference = gen.clone();
+
+        for mut partition in gen.into_partitioned_generators(3) {
+            for (index, code) in partition.enumerate_codes().take(50) {
+                assert_eq!(reference.code_at(index), code);
+                assert_eq!(Some(index), reference.decode_vec(&code));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursor_token() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(4);
+        let mut restored = gen.clone();
+
+        for _ in 0..500 {
+            gen.next_vec();
+        }
+
+        let token = gen.cursor_token();
+        assert_eq!(18, token.len());
+        restored.apply_cursor_token(&token).unwrap();
+
+        for _ in 0..500 {
+            assert_eq!(gen.next_vec(), restored.next_vec());
+        }
+    }
+
+    #[test]
+    fn test_cursor_token_partitioned() {
+        let mut gen = ShortCodeGenerator::new_numeric(3).into_partitioned_generators(3)[1].clone();
+        let mut restored = gen.clone();
+
+        gen.next_vec();
+        restored.apply_cursor_token(&gen.cursor_token()).unwrap();
+        for _ in 0..100 {
+            assert_eq!(gen.next_vec(), restored.next_vec());
+        }
+
+        let mut unpartitioned = ShortCodeGenerator::new_numeric(3);
+        assert_eq!(
+            Err(TokenError::Mismatch),
+            unpartitioned.apply_cursor_token(&gen.cursor_token())
+        );
+    }
+
+    #[test]
+    fn test_cursor_token_mismatch() {
+        let gen = ShortCodeGenerator::new_numeric(4);
+        let token = gen.cursor_token();
+
+        let mut other_length = ShortCodeGenerator::new_numeric(5);
+        assert_eq!(
+            Err(TokenError::Mismatch),
+            other_length.apply_cursor_token(&token)
+        );
+
+        let mut other_alphabet = ShortCodeGenerator::new_lowercase_alphanumeric(4);
+        assert_eq!(
+            Err(TokenError::Mismatch),
+            other_alphabet.apply_cursor_token(&token)
+        );
+
+        let mut same = gen.clone();
+        assert_eq!(
+            Err(TokenError::Malformed),
+            same.apply_cursor_token("not a token")
+        );
+        assert_eq!(Err(TokenError::Malformed), same.apply_cursor_token(""));
+    }
+
+    #[test]
+    fn test_cursor_token_same_size_alphabet_mismatch() {
+        let digits = ShortCodeGenerator::with_alphabet_and_rng(
+            "0123456789".chars().collect(),
+            4,
+            ChaCha12Rng::seed_from_u64(1),
+        );
+        let mut letters = ShortCodeGenerator::with_alphabet_and_rng(
+            "abcdefghij".chars().collect(),
+            4,
+            ChaCha12Rng::seed_from_u64(1),
+        );
+
+        assert_eq!(
+            Err(TokenError::Mismatch),
+            letters.apply_cursor_token(&digits.cursor_token())
+        );
+    }
+
+    #[test]
+    fn test_next_be_bytes() {
+        let mut gen = ShortCodeGenerator::new_numeric(4);
+        let mut reference = gen.clone();
+
+        let mut seen = HashSet::new();
+        for _ in 0..10_000 {
+            let value = u64::from_be_bytes(gen.next_be_bytes());
+            assert!(value < gen.capacity());
+            assert_eq!(reference.next_u64(), value);
+            assert!(seen.insert(value));
+        }
+    }
+
+    #[test]
+    fn test_try_from_alphabet_str() {
+        let mut gen = ShortCodeGenerator::try_from_alphabet_str("abcdef", 3).unwrap();
+        assert_eq!(216, gen.capacity());
+        assert!(gen.next_string().chars().all(|c| "abcdef".contains(c)));
+
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            ShortCodeGenerator::try_from_alphabet_str("", 3).err()
+        );
+        assert_eq!(
+            Some(AlphabetError::SingleSymbol),
+            ShortCodeGenerator::try_from_alphabet_str("a", 3).err()
+        );
+        assert_eq!(
+            Some(AlphabetError::DuplicateSymbol { symbol: 'b' }),
+            ShortCodeGenerator::try_from_alphabet_str("abcb", 3).err()
+        );
+        assert_eq!(
+            Some(AlphabetError::InvalidSymbol { symbol: ' ' }),
+            ShortCodeGenerator::try_from_alphabet_str("ab c", 3).err()
+        );
+        assert_eq!(
+            Some(AlphabetError::InvalidSymbol { symbol: '\n' }),
+            ShortCodeGenerator::try_from_alphabet_str("abc\n", 3).err()
+        );
+    }
+
+    #[test]
+    fn test_next_string_reversed() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(5);
+        let mut reference = gen.clone();
+
+        for _ in 0..100 {
+            let expected: String = reference.next_string().chars().rev().collect();
+            assert_eq!(expected, gen.next_string_reversed());
+        }
+    }
+
+    #[test]
+    fn test_try_shrink_alphabet() {
+        let mut gen = ShortCodeGenerator::new_alphanumeric(3);
+        gen.try_shrink_alphabet("0123456789".chars().collect())
+            .unwrap();
+        assert_eq!(1000, gen.capacity());
+
+        let codes: HashSet<String> = (0..1000).map(|_| gen.next_string()).collect();
+        assert_eq!(1000, codes.len());
+        assert!(codes
+            .iter()
+            .all(|code| code.chars().all(|c| c.is_ascii_digit())));
+    }
+
+    #[test]
+    fn test_try_shrink_alphabet_errors() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        assert_eq!(Err(AlphabetError::Empty), gen.try_shrink_alphabet(vec![]));
+
+        gen.next_vec();
+        let position = gen.position();
+        assert_eq!(
+            Err(AlphabetError::CodesIssued),
+            gen.try_shrink_alphabet("01".chars().collect())
+        );
+        assert_eq!(1000, gen.capacity());
+        assert_eq!(position, gen.position());
+    }
+
+    #[test]
+    fn test_try_shrink_alphabet_validates() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        assert_eq!(
+            Err(AlphabetError::SizeMismatch {
+                expected: 10,
+                actual: 11
+            }),
+            gen.try_shrink_alphabet("0123456789a".chars().collect())
+        );
+        assert_eq!(
+            Err(AlphabetError::SingleSymbol),
+            gen.try_shrink_alphabet(vec!['0'])
+        );
+        assert_eq!(
+            Err(AlphabetError::RepeatedSymbol { index: 2 }),
+            gen.try_shrink_alphabet(vec!['0', '1', '0'])
+        );
+
+        let mut constrained = gen.clone().with_banned_leading(vec!['0']);
+        assert!(matches!(
+            constrained.try_shrink_alphabet("01234".chars().collect()),
+            Err(AlphabetError::IncompatibleSetting { .. })
+        ));
+
+        let mut partition = gen.clone().into_partitioned_generators(2).remove(1);
+        assert!(matches!(
+            partition.try_shrink_alphabet("01234".chars().collect()),
+            Err(AlphabetError::IncompatibleSetting { .. })
+        ));
+
+        let mut marked = gen.clone().with_partition_marker(7, 8);
+        assert!(matches!(
+            marked.try_shrink_alphabet("01234".chars().collect()),
+            Err(AlphabetError::IncompatibleSetting { .. })
+        ));
+
+        // None of the errors changed the generator.
+        assert_eq!(1000, gen.capacity());
+        assert_eq!(1000, constrained.capacity());
+        assert_eq!(1000, partition.capacity());
+    }
+
+    #[test]
+    fn test_next_ordered() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let reference = gen.clone();
+
+        let codes: Vec<OrderedCode<char>> = (0..150).map(|_| gen.next_ordered()).collect();
+        assert!(codes.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(reference.code_at(5), codes[5].code());
+        assert_eq!(5, codes[5].position());
+        assert!(format!("{:?}", codes[5]).contains("position: 5"));
+
+        // Codes after a length increase sort after the shorter ones.
+        assert_eq!(3, codes[100].code().len());
+        assert_eq!(0, codes[100].position());
+
+        let mut shuffled = codes.clone();
+        shuffled.reverse();
+        shuffled.sort();
+        assert_eq!(codes, shuffled);
+    }
+
+    #[test]
+    fn test_position_constraint() {
+        let letters: Vec<char> = "abcdef".chars().collect();
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(2)
+            .with_position_constraint(0, letters.clone())
+            .exhaustion_strategy(ExhaustionStrategy::Panic);
+
+        // 6 letters at the first position, any of 36 symbols at the second.
+        let codes: HashSet<String> = (0..6 * 36).map(|_| gen.next_string()).collect();
+        assert_eq!(6 * 36, codes.len());
+        assert!(codes
+            .iter()
+            .all(|code| letters.contains(&code.chars().next().unwrap())));
+    }
+
+    #[test]
+    fn test_banned_leading() {
+        let mut gen = ShortCodeGenerator::new_numeric(3)
+            .with_banned_leading(vec!['0', '9', 'x'])
+            .exhaustion_strategy(ExhaustionStrategy::Panic);
+
+        let codes: HashSet<String> = (0..800).map(|_| gen.next_string()).collect();
+        assert_eq!(800, codes.len());
+        assert!(codes
+            .iter()
+            .all(|code| !code.starts_with('0') && !code.starts_with('9')));
+
+        // Only banned codes are left.
+        assert!(std::panic::catch_unwind(move || gen.next_string()).is_err());
+    }
+
+    #[test]
+    fn test_banned_leading_remaining() {
+        let mut gen = ShortCodeGenerator::new_numeric(3)
+            .with_banned_leading(vec!['0', '1', '2'])
+            .exhaustion_strategy(ExhaustionStrategy::IncreaseLength);
+        let remaining = gen.remaining();
+
+        let emitted = std::iter::from_fn(|| Some(gen.next_string()))
+            .take_while(|code| code.len() == 3)
+            .count();
+        assert_eq!(700, remaining);
+        assert_eq!(remaining, emitted as u64);
+    }
+
+    #[test]
+    fn test_banned_leading_rendered_first() {
+        let gen = ShortCodeGenerator::new_numeric(3).with_banned_leading(vec!['0']);
+        for result in [
+            std::panic::catch_unwind(|| gen.clone().with_partition_marker(1, 2)),
+            std::panic::catch_unwind(|| gen.clone().with_display_width(4)),
+            std::panic::catch_unwind(|| {
+                ShortCodeGenerator::new_numeric(3)
+                    .with_partition_marker(1, 2)
+                    .with_banned_leading(vec!['0'])
+            }),
+            std::panic::catch_unwind(|| {
+                ShortCodeGenerator::new_numeric(3)
+                    .with_display_width(4)
+                    .with_banned_leading(vec!['0'])
+            }),
+        ] {
+            assert!(result.is_err());
+        }
+
+        // A display width that doesn't pad leaves the first symbol in front.
+        let mut gen = gen.with_display_width(3);
+        assert!((0..100).all(|_| gen.next_vec()[0] != '0'));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't ban every symbol")]
+    fn test_banned_leading_everything() {
+        let _ = ShortCodeGenerator::with_alphabet(vec![1, 2], 4).with_banned_leading(vec![2, 1]);
+    }
+
+    #[test]
+    fn test_position_constraint_partitioned() {
+        let gen = ShortCodeGenerator::new_numeric(3)
+            .with_position_constraint(2, vec!['1', '2', '3'])
+            .exhaustion_strategy(ExhaustionStrategy::Panic);
+
+        // Each partition owns about 100 of the 300 allowed codes.
+        let mut seen = HashSet::new();
+        for mut partition in gen.into_partitioned_generators(3) {
+            for _ in 0..50 {
+                let code = partition.next_vec();
+                assert!("123".contains(code[2]));
+                assert!(seen.insert(code));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "allowed symbol")]
+    fn test_position_constraint_unsatisfiable() {
+        ShortCodeGenerator::new_numeric(3).with_position_constraint(0, vec!['x']);
+    }
+
+    #[test]
+    fn test_recurrence_interval() {
+        let gen = ShortCodeGenerator::new_numeric(2);
+        assert_eq!(None, gen.recurrence_interval());
+        assert_eq!(
+            None,
+            gen.clone()
+                .exhaustion_strategy(ExhaustionStrategy::Panic)
+                .recurrence_interval()
+        );
+
+        let mut gen = gen.exhaustion_strategy(ExhaustionStrategy::Cycle);
+        assert_eq!(Some(100), gen.recurrence_interval());
+
+        let first = gen.next_vec();
+        for _ in 1..100 {
+            assert_ne!(first, gen.next_vec());
+        }
+        assert_eq!(first, gen.next_vec());
+    }
+
+    #[test]
+    fn test_recurrence_interval_partitioned() {
+        let gen = ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+
+        for (total, expected) in [(4, 25), (3, 100)] {
+            for mut partition in gen.clone().into_partitioned_generators(total) {
+                assert_eq!(Some(expected), partition.recurrence_interval());
+
+                let first = partition.next_vec();
+                for _ in 1..expected {
+                    assert_ne!(first, partition.next_vec());
+                }
+                assert_eq!(first, partition.next_vec());
+            }
+        }
+
+        for partition in gen.disjoint_partitions(3) {
+            assert_eq!(
+                partition.partition_usage().1,
+                partition.recurrence_interval().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_recurrence_interval_max_length() {
+        let mut gen = ShortCodeGenerator::new_numeric(1)
+            .with_max_length(2)
+            .max_length_fallback(ExhaustionStrategy::Cycle);
+        assert_eq!(None, gen.recurrence_interval());
+
+        for _ in 0..10 {
+            gen.next_vec();
+        }
+        gen.next_vec();
+        assert_eq!(Some(100), gen.recurrence_interval());
+    }
+
+    #[test]
+    fn test_next_int_checked() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let first = gen.next_int_checked();
+        assert!(!first.1);
+
+        for _ in 1..100 {
+            assert!(!gen.next_int_checked().1);
+        }
+        assert_eq!((first.0, true), gen.next_int_checked());
+        assert!(!gen.next_int_checked().1);
+    }
+
+    #[test]
+    fn test_next_int_checked_partitioned() {
+        let gen = ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let mut partition = gen.into_partitioned_generators(3).remove(2);
+
+        // Partition 2 emits indices 2, 5, ..., 98, then wraps to 1.
+        let wraps: Vec<bool> = (0..34).map(|_| partition.next_int_checked().1).collect();
+        assert_eq!(vec![33], (0..34).filter(|&i| wraps[i]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_epoch() {
+        let mut gen = ShortCodeGenerator::new_numeric(3).with_epoch(2);
+        let mut reference = gen.clone();
+        reference.epoch = None;
+
+        for _ in 0..10 {
+            assert_eq!(format!("00{}", reference.next_string()), gen.next_string());
+        }
+
+        gen.advance_epoch();
+        for _ in 0..10 {
+            assert_eq!(format!("01{}", reference.next_string()), gen.next_string());
+        }
+
+        for _ in 0..10 {
+            gen.advance_epoch();
+        }
+        assert!(gen.next_string().starts_with("11"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Epoch overflows")]
+    fn test_epoch_overflow() {
+        let mut gen = ShortCodeGenerator::new_numeric(3).with_epoch(1);
+        for _ in 0..10 {
+            gen.advance_epoch();
+        }
+    }
+
+    #[test]
+    fn test_config_fingerprint() {
+        let numeric: Vec<char> = "0123456789".chars().collect();
+        let gen = ShortCodeGenerator::with_alphabet_and_seed_bytes(numeric.clone(), 4, [1; 32]);
+
+        let mut same =
+            ShortCodeGenerator::with_alphabet_and_seed_bytes(numeric.clone(), 4, [1; 32])
+                .exhaustion_strategy(ExhaustionStrategy::Cycle);
+        same.next_vec();
+        assert_eq!(gen.config_fingerprint(), same.config_fingerprint());
+
+        let other_seed =
+            ShortCodeGenerator::with_alphabet_and_seed_bytes(numeric.clone(), 4, [2; 32]);
+        let other_length = ShortCodeGenerator::with_alphabet_and_seed_bytes(numeric, 5, [1; 32]);
+        let other_alphabet = ShortCodeGenerator::with_alphabet_and_seed_bytes(
+            "9876543210".chars().collect(),
+            4,
+            [1; 32],
+        );
+
+        for other in [other_seed, other_length, other_alphabet] {
+            assert_ne!(gen.config_fingerprint(), other.config_fingerprint());
+        }
+    }
+
+    #[test]
+    fn test_rev() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        let start = gen.cycle_start();
+
+        let forward = gen.next_n(500);
+        let mut backward: Vec<Vec<char>> = gen.rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        assert_eq!(0, gen.position());
+        assert_eq!(start, gen.next_vec());
+    }
+
+    #[test]
+    fn test_rev_block() {
+        let mut gen = ShortCodeGenerator::new_numeric(3)
+            .disjoint_partitions(4)
+            .remove(2);
+
+        let forward = gen.next_n(100);
+        assert_eq!(forward.len(), gen.rev().count());
+        assert_eq!(forward[..10], gen.next_n(10)[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ShortCodeGenerator exhausted: base=10 length=2 capacity=100")]
+    fn test_exhaustion_panic_message() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Panic);
+        for _ in 0..101 {
+            gen.next_vec();
+        }
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        let mut reference = gen.clone();
+
+        let mut a = gen.fork();
+        let mut b = gen.fork();
+        assert_ne!(a.config_fingerprint(), b.config_fingerprint());
+        assert_ne!(gen.config_fingerprint(), a.config_fingerprint());
+
+        // Forking doesn't change the parent's sequence.
+        for _ in 0..100 {
+            assert_eq!(reference.next_vec(), gen.next_vec());
+        }
+
+        // Each fork covers the whole space.
+        for fork in [&mut a, &mut b] {
+            let seen: HashSet<String> = (0..1000).map(|_| fork.next_string()).collect();
+            assert_eq!(1000, seen.len());
+        }
+    }
+
+    #[test]
+    fn test_fork_sequence() {
+        let mut gen = ShortCodeGenerator::new_numeric(1).with_epoch(1);
+        for _ in 0..15 {
+            gen.next_vec();
+        }
+        gen.advance_epoch();
+
+        let mut fork = gen.fork();
+        assert_eq!(0, fork.generated_count());
+        assert_eq!(0, fork.growth_count());
+
+        // The fork starts over at epoch zero, with none of the parent's
+        // earlier sequences.
+        let code = fork.next_vec();
+        assert_eq!('0', code[0]);
+        assert_eq!(2, gen.possible_indices("07").len());
+        assert_eq!(1, fork.possible_indices("07").len());
+
+        let parent: Vec<u64> = (0..100).map(|_| gen.next_int()).collect();
+        let forked: Vec<u64> = (0..100).map(|_| fork.next_int()).collect();
+        assert_ne!(parent, forked);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't fork a generator with integer source Sequential.")]
+    fn test_fork_sequential() {
+        let mut gen = ShortCodeGenerator::new_numeric(3).integer_source(IntegerSource::Sequential);
+        gen.fork();
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        let gen = ShortCodeGenerator::with_alphabet(vec![0u8, 1], 10);
+        assert_eq!(10.0, gen.entropy_bits());
+        assert_eq!(1.0 / 1024.0, gen.guess_probability());
+
+        let gen = ShortCodeGenerator::new_lowercase_alphanumeric(5);
+        assert!((gen.entropy_bits() - (gen.capacity() as f64).log2()).abs() < 1e-9);
+        assert_eq!(1.0 / gen.capacity() as f64, gen.guess_probability());
+    }
+
+    #[test]
+    fn test_fill_unique() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let mut set = HashSet::new();
+
+        assert_eq!(100, gen.remaining());
+        assert_eq!(60, gen.fill_unique(&mut set, 60));
+        assert_eq!(40, gen.remaining());
+
+        // Only 40 codes remain before the cycle repeats.
+        assert_eq!(40, gen.fill_unique(&mut set, 60));
+        assert_eq!(100, set.len());
+    }
+
+    #[test]
+    fn test_with_increment() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let mut incremented = gen.clone().with_increment(7);
+
+        let codes: Vec<String> = (0..100).map(|_| incremented.next_string()).collect();
+        assert_eq!(100, codes.iter().collect::<HashSet<_>>().len());
+        assert_ne!(
+            codes,
+            (0..100).map(|_| gen.next_string()).collect::<Vec<_>>()
+        );
+
+        // The increment is kept when the length increases.
+        incremented.next_string();
+        assert!(incremented.verify_full_period());
+        assert_eq!(Some(7), incremented.increment);
+        assert_eq!(7, incremented.lcm.params().2);
+    }
+
+    #[test]
+    #[should_panic(expected = "not coprime")]
+    fn test_with_increment_not_coprime() {
+        ShortCodeGenerator::new_numeric(2).with_increment(5);
+    }
+
+    #[test]
+    fn test_cycling_iter() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(1).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let mut reference = gen.clone();
+        let mut wraps = Vec::new();
+
+        let codes: Vec<Vec<char>> = gen
+            .cycling_iter(|cycle| wraps.push(cycle))
+            .take(35)
+            .collect();
+        assert_eq!(vec![1, 2, 3], wraps);
+        assert_eq!(reference.next_n(35), codes);
+    }
+
+    #[test]
+    fn test_with_alphabet_and_rngs() {
+        let alphabet: Vec<char> = "0123456789".chars().collect();
+        let gen = |seed: u8, offset: u8| {
+            ShortCodeGenerator::with_alphabet_and_rngs(
+                alphabet.clone(),
+                4,
+                ChaCha12Rng::from_seed([seed; 32]),
+                ChaCha12Rng::from_seed([offset; 32]),
+            )
+        };
+
+        let codes = |mut gen: ShortCodeGenerator<char>| -> Vec<String> {
+            (0..10).map(|_| gen.next_string()).collect()
+        };
+
+        assert_eq!(codes(gen(1, 2)), codes(gen(1, 2)));
+        assert_ne!(codes(gen(1, 2)), codes(gen(1, 3)));
+        assert_ne!(codes(gen(1, 2)), codes(gen(4, 2)));
+
+        // Changing only the offset rng shifts every value by the same amount.
+        let (mut a, mut b) = (gen(1, 2), gen(1, 3));
+        let shift = (b.next_int() + 10_000 - a.next_int()) % 10_000;
+        for _ in 0..100 {
+            assert_eq!((a.next_int() + shift) % 10_000, b.next_int());
+        }
+
+        let mut codes: HashSet<String> = HashSet::new();
+        let mut gen = gen(5, 6);
+        for _ in 0..10_000 {
+            assert!(codes.insert(gen.next_string()));
+        }
+    }
+
+    #[test]
+    fn test_symbol_index_map() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(5);
+        let map = gen.symbol_index_map();
+        assert_eq!(36, map.len());
+        assert_eq!(0, map[&'0']);
+        assert_eq!(10, map[&'a']);
+
+        for _ in 0..100 {
+            let mut reference = gen.clone();
+            let value = reference.next_int();
+            let code = gen.next_vec();
+            let decoded = code
+                .iter()
+                .rev()
+                .fold(0u64, |value, symbol| value * 36 + map[symbol] as u64);
+            assert_eq!(value, decoded);
+        }
+    }
+
+    #[test]
+    fn test_symbol_frequency() {
+        for (alphabet, length) in [("01", 6), ("0123456789", 3), ("abcdef", 4)] {
+            let mut gen = ShortCodeGenerator::with_alphabet(alphabet.chars().collect(), length);
+            gen.next_vec();
+
+            let frequency = gen.symbol_frequency();
+            let expected = gen.capacity() * length as u64 / alphabet.len() as u64;
+            assert_eq!(alphabet.len(), frequency.len());
+            assert!(frequency.values().all(|&count| count == expected));
+        }
+
+        let gen = ShortCodeGenerator::with_alphabet("0123".chars().collect(), 3)
+            .with_output_whitening()
+            .with_check_symbol();
+        let frequency = gen.symbol_frequency();
+        assert!(frequency.values().all(|&count| count == 48));
+    }
+
+    #[test]
+    fn test_accepts_length() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        let old_codes: Vec<String> = (0..100).map(|_| gen.next_string()).collect();
+        assert!(gen.accepts_length(2));
+
+        let new_code = gen.next_string();
+        assert_eq!(3, new_code.len());
+        assert!(gen.accepts_length(3));
+        assert!(!gen.accepts_length(2));
+
+        assert_eq!(
+            Some(0),
+            gen.decode_vec(&new_code.chars().collect::<Vec<_>>())
+        );
+        for code in &old_codes {
+            assert_eq!(None, gen.decode_vec(&code.chars().collect::<Vec<_>>()));
+            let result = gen.validate_full(code, 2);
+            assert!(!result.length_ok && !result.is_valid());
+            assert_eq!(None, result.partition);
+        }
+
+        let gen = ShortCodeGenerator::new_numeric(4)
+            .with_epoch(2)
+            .with_check_symbol();
+        assert!(gen.accepts_length(7));
+        assert!(!gen.accepts_length(4));
     }
 
-    /// Create a short code generator using upper and lowercase alphanumeric characters.
-    #[cfg(feature = "getrandom")]
-    pub fn new_alphanumeric(length: usize) -> Self {
-        Self::with_alphabet(
-            "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
-                .chars()
-                .collect(),
-            length,
-        )
+    #[test]
+    fn test_codes_per_window() {
+        let gen = ShortCodeGenerator::new_numeric(4);
+        assert_eq!(10_000, gen.codes_per_window(1));
+        assert_eq!(27, gen.codes_per_window(365));
+        assert_eq!(0, gen.codes_per_window(20_000));
+
+        let partitions = gen.clone().into_partitioned_generators(4);
+        for partition in &partitions {
+            assert_eq!(2_500, partition.codes_per_window(1));
+            assert_eq!(6, partition.codes_per_window(365));
+        }
+
+        let uneven = ShortCodeGenerator::new_numeric(1).into_partitioned_generators(3);
+        let shares: Vec<u64> = uneven.iter().map(|p| p.codes_per_window(1)).collect();
+        assert_eq!(10, shares.iter().sum::<u64>());
+
+        let blocks = gen.disjoint_partitions(5);
+        assert_eq!(1_000, blocks[0].codes_per_window(2));
     }
 
-    /// Create a short code generator using uppercase characters.
-    #[cfg(feature = "getrandom")]
-    pub fn new_uppercase(length: usize) -> Self {
-        Self::with_alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect(), length)
+    #[test]
+    #[should_panic(expected = "Number of windows must be positive.")]
+    fn test_codes_per_window_zero() {
+        ShortCodeGenerator::new_numeric(4).codes_per_window(0);
     }
 
-    /// Return the next short code, represented as a string.
-    /// All `next_*` calls are equivalent to each other in terms of the
-    /// resulting state of self.
-    pub fn next_string(&mut self) -> String {
-        self.next_vec().into_iter().collect()
+    #[test]
+    fn test_distinct_issued() {
+        let mut gen =
+            ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        assert_eq!(0, gen.distinct_issued());
+
+        for i in 1..=250 {
+            gen.next_int();
+            assert_eq!(i, gen.generated_count());
+            assert_eq!(i.min(100), gen.distinct_issued());
+        }
+
+        gen.unget();
+        assert_eq!(249, gen.generated_count());
+        assert_eq!(100, gen.distinct_issued());
+
+        let mut partitions = ShortCodeGenerator::new_numeric(2)
+            .exhaustion_strategy(ExhaustionStrategy::Cycle)
+            .into_partitioned_generators(4);
+        for i in 1..=60 {
+            partitions[1].next_int();
+            assert_eq!(i, partitions[1].generated_count());
+            assert_eq!(i.min(25), partitions[1].distinct_issued());
+        }
+        assert_eq!(0, partitions[0].generated_count());
+
+        let mut grown = ShortCodeGenerator::new_numeric(1);
+        for _ in 0..15 {
+            grown.next_int();
+        }
+        assert_eq!(15, grown.distinct_issued());
     }
-}
 
-impl<T: Copy> ShortCodeGenerator<T> {
-    pub fn into_partitioned_generators(self, generators: u32) -> Vec<Self> {
-        (0..generators).map(
-            move |offset| {
-                let mut gen = self.clone();
-                if gen.skip.is_some() {
-                    panic!("Can't use into_partitioned_generators on a generator that is already parallel.");
-                }
+    #[test]
+    fn test_remap_alphabet() {
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        let mut old = gen.clone();
+        let before = gen.next_vec();
+        old.next_vec();
 
-                for _ in 0..offset {
-                    gen.next_int();
-                }
-                gen.skip_before_next = false;
-                gen.skip = Some(generators - 1);
+        let new_alphabet: Vec<char> = "9876543210".chars().collect();
+        gen.remap_alphabet(new_alphabet.clone()).unwrap();
 
-                gen
-            }
-        ).collect()
+        for _ in 0..100 {
+            let old_code = old.next_vec();
+            let code = gen.next_vec();
+            let expected: Vec<char> = old_code
+                .iter()
+                .map(|c| new_alphabet[c.to_digit(10).unwrap() as usize])
+                .collect();
+            assert_eq!(expected, code);
+            assert_eq!(old.decode_vec(&old_code), gen.decode_vec(&code));
+        }
+
+        // Codes issued before the change decode with the old alphabet.
+        assert_eq!(Some(0), old.decode_vec(&before));
+
+        assert_eq!(
+            Err(AlphabetError::SizeMismatch {
+                expected: 10,
+                actual: 3
+            }),
+            gen.remap_alphabet(vec!['a', 'b', 'c'])
+        );
+        assert_eq!(
+            Err(AlphabetError::RepeatedSymbol { index: 9 }),
+            gen.remap_alphabet("abcdefghia".chars().collect())
+        );
+        assert_eq!(Some(1), gen.decode_vec(&gen.code_at(1)));
     }
 
-    /// Create a short code generator using a given alphabet, using the given
-    /// ChaCha12Rng random number generator.
-    pub fn with_alphabet_and_rng(alphabet: Vec<T>, length: usize, mut rng: ChaCha12Rng) -> Self {
-        use lcm::generate_a;
+    #[test]
+    fn test_remap_alphabet_constraints_follow_index() {
+        let mut gen = ShortCodeGenerator::new_numeric(2).with_banned_leading(vec!['0']);
+        gen.remap_alphabet("abcdefghij".chars().collect()).unwrap();
 
-        let m_base = alphabet.len() as u32;
-        let m = (m_base as u64).pow(length as u32);
-        let a = generate_a(m_base) as u64;
-        let lcm_seed = rng.gen_range(0..m) as u64;
-        let offset = rng.gen_range(0..m) as u64;
+        // The ban on index 0 now applies to 'a'.
+        for _ in 0..90 {
+            assert!(!gen.next_string().starts_with('a'));
+        }
+    }
 
-        Self {
-            alphabet,
-            lcm: LinearCongruentMultiplier::new(lcm_seed, m, 1, a),
-            offset,
-            length: length as u32,
-            exhaustion_strategy: ExhaustionStrategy::default(),
-            rng: Some(rng),
-            skip: None,
-            skip_before_next: false,
+    #[cfg(feature = "signature")]
+    #[test]
+    fn test_signature() {
+        let key = b"secret".to_vec();
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(4)
+            .with_check_symbol()
+            .with_signature(key.clone(), 3);
+
+        for _ in 0..100 {
+            let code = gen.next_string();
+            assert_eq!(8, code.len());
+            assert!(gen.verify_signature(&code));
+
+            // The signature doesn't interfere with the check symbol or
+            // decoding.
+            let symbols: Vec<char> = code.chars().collect();
+            assert!(gen.has_valid_check_symbol(&symbols));
+            assert_eq!(Some(gen.position() - 1), gen.decode_vec(&symbols[..4]));
         }
+
+        // Only the same key verifies.
+        let code = gen.next_string();
+        let other = gen.clone().with_signature(b"other".to_vec(), 3);
+        assert!(!other.verify_signature(&code));
+        assert!(!ShortCodeGenerator::new_lowercase_alphanumeric(4).verify_signature(&code));
+
+        // The key is neither serialized nor shown by `Debug`, and has to be
+        // set again after deserializing.
+        let json = serde_json::to_string(&gen).unwrap();
+        assert!(!json.contains("\"key\""));
+        assert!(!format!("{:?}", gen).contains(&format!("{:?}", key)));
+        let restored: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+        assert!(!restored.verify_signature(&code));
+        let mut restored = restored.with_signature(key, 3);
+        assert!(restored.verify_signature(&code));
+        assert_eq!(gen.next_string(), restored.next_string());
     }
 
-    /// Create a short code generator using a given alphabet.
-    #[cfg(feature = "getrandom")]
-    pub fn with_alphabet(alphabet: Vec<T>, length: usize) -> Self {
-        let mut seed: [u8; 32] = Default::default();
-        getrandom::getrandom(&mut seed).expect("Error getting entropy.");
-        let rng = ChaCha12Rng::from_seed(seed);
-        Self::with_alphabet_and_rng(alphabet, length, rng)
+    #[cfg(not(feature = "signature"))]
+    #[test]
+    fn test_signature_needs_feature() {
+        let gen = ShortCodeGenerator::new_numeric(4);
+        let mut json = serde_json::to_value(&gen).unwrap();
+        json["signature"] = serde_json::json!({ "symbols": 3 });
+
+        let err = serde_json::from_value::<ShortCodeGenerator<char>>(json).unwrap_err();
+        assert!(err.to_string().contains("Need crate feature signature"));
     }
 
-    fn step(&mut self) -> u64 {
-        if self.lcm.exhausted() {
-            match self.exhaustion_strategy {
-                ExhaustionStrategy::Cycle => {}
-                ExhaustionStrategy::Panic => panic!("Exhausted."),
-                ExhaustionStrategy::IncreaseLength => {
-                    let rng = if let Some(rng) = self.rng.clone() {
-                        rng
-                    } else {
-                        #[cfg(feature = "getrandom")]
-                        {
-                            let mut seed: [u8; 32] = Default::default();
-                            getrandom::getrandom(&mut seed).expect("Error getting entropy.");
-                            ChaCha12Rng::from_seed(seed)
-                        }
-
-                        #[cfg(not(feature = "getrandom"))]
-                        panic!("Need crate feature getrandom to increase the length of a pre-0.1.4 ShortCodeGenerator. See https://github.com/paulgb/tiny_id/issues/2")
-                    };
-
-                    // These values of self are initialized by with_alphabet_and_rng, so we preserve them
-                    // on the stack and overwrite them.
-                    let skip = self.skip;
-                    let skip_before_next = self.skip_before_next;
-
-                    *self = ShortCodeGenerator::with_alphabet_and_rng(
-                        core::mem::take(&mut self.alphabet),
-                        self.length as usize + 1,
-                        rng,
-                    );
-
-                    self.skip = skip;
-                    self.skip_before_next = skip_before_next;
+    #[cfg(feature = "signature")]
+    #[test]
+    #[should_panic(expected = "Signature key is not set")]
+    fn test_signature_key_not_serialized() {
+        let gen = ShortCodeGenerator::new_numeric(4).with_signature(b"secret".to_vec(), 3);
+        let json = serde_json::to_string(&gen).unwrap();
+        let mut restored: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+        restored.next_string();
+    }
+
+    #[cfg(feature = "signature")]
+    #[test]
+    fn test_signature_tampering() {
+        // Seeded, since a random code has a tiny chance of a tampered variant
+        // that happens to verify.
+        let alphabet: Vec<char> = "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect();
+        let gen =
+            ShortCodeGenerator::with_alphabet_for_test(alphabet, 6).with_signature(vec![7; 32], 4);
+        let code = gen.clone().next_string();
+        assert!(gen.verify_signature(&code));
+
+        // Changing any one symbol is detected.
+        for i in 0..code.len() {
+            for replacement in "0123456789abcdefghijklmnopqrstuvwxyz".chars() {
+                let mut tampered: Vec<char> = code.chars().collect();
+                if tampered[i] == replacement {
+                    continue;
                 }
+                tampered[i] = replacement;
+                let tampered: String = tampered.into_iter().collect();
+                assert!(!gen.verify_signature(&tampered), "{}", tampered);
             }
         }
-        self.lcm.next()
+
+        assert!(!gen.verify_signature(&code[..9]));
+        assert!(!gen.verify_signature(&format!("{}a", code)));
+        assert!(!gen.verify_signature("ABCDEFGHIJ"));
     }
 
-    /// Return the next short code, represented as an integer.
-    /// All `next_*` calls are equivalent to each other in terms of the
-    /// resulting state of self.
-    pub fn next_int(&mut self) -> u64 {
-        if self.skip_before_next {
-            for _ in 0..self.skip.unwrap_or_default() {
-                println!("h0");
-                self.step();
-            }    
-        } else {
-            self.skip_before_next = true;
+    #[cfg(feature = "signature")]
+    #[test]
+    #[should_panic(expected = "signature symbols")]
+    fn test_signature_too_long() {
+        let _ = ShortCodeGenerator::new_numeric(4).with_signature(vec![1], 78);
+    }
+
+    #[test]
+    fn test_display_width() {
+        let mut gen = ShortCodeGenerator::new_numeric(1)
+            .exhaustion_strategy(ExhaustionStrategy::IncreaseLengthPadded)
+            .with_display_width(3);
+
+        let mut seen = HashSet::new();
+        while seen.len() < 1000 {
+            let code = gen.next_vec();
+            let padding = 3 - gen.length as usize;
+            assert_eq!(3, code.len());
+            assert!(code[..padding].iter().all(|&symbol| symbol == '0'));
+            assert!(gen.decode_vec(&code[padding..]).is_some());
+            assert!(seen.insert(code));
         }
 
-        let mut result = self.step();
-        result = (result + self.offset) % self.lcm.m;
+        // Codes grow past the width rather than being truncated.
+        assert_eq!(4, gen.next_string().len());
+    }
+
+    #[test]
+    fn test_display_width_validate() {
+        let mut gen = ShortCodeGenerator::new_numeric(2)
+            .with_display_width(4)
+            .with_check_symbol();
+        let code = gen.next_string();
+        assert_eq!(5, code.len());
+        assert!(code.starts_with("00"));
+
+        let result = gen.validate_full(&code, 1);
+        assert!(result.is_valid());
+        assert_eq!(Some(0), result.partition);
 
-        result
+        // Padding must be the first symbol.
+        let bad: String = format!("1{}", &code[1..]);
+        assert_eq!(None, gen.validate_full(&bad, 1).partition);
     }
 
-    /// Deprecated alias for [`ShortCodeGenerator::next_vec`].
-    #[deprecated(
-        since = "0.1.4",
-        note = "Deprecated to avoid confusion with Iterator::next. Use next_vec instead."
-    )]
-    pub fn next(&mut self) -> Vec<T> {
-        self.next_vec()
+    #[test]
+    #[should_panic(expected = "Display width 2 is less than the code length 3.")]
+    fn test_display_width_too_small() {
+        let _ = ShortCodeGenerator::new_numeric(3).with_display_width(2);
     }
 
-    /// Return the next short code, represented as a vector.
-    /// All `next_*` calls are equivalent to each other in terms of the
-    /// resulting state of self.
-    pub fn next_vec(&mut self) -> Vec<T> {
-        let mut result = Vec::with_capacity(self.length as usize);
-        let alphabet_size = self.alphabet.len() as u64;
-        let mut value = self.next_int();
+    #[test]
+    fn test_check_symbol() {
+        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(4).with_check_symbol();
+        let reference = gen.clone();
 
-        for _ in 0..self.length {
-            result.push(self.alphabet[(value % alphabet_size) as usize]);
-            value /= alphabet_size;
+        for i in 0..1000 {
+            let code = gen.next_vec();
+            assert_eq!(5, code.len());
+            assert!(gen.has_valid_check_symbol(&code));
+            assert_eq!(Some(i), reference.decode_vec(&code[..4]));
+
+            let mut typo = code.clone();
+            typo[i as usize % 5] = if typo[i as usize % 5] == 'x' {
+                'y'
+            } else {
+                'x'
+            };
+            assert!(!gen.has_valid_check_symbol(&typo));
         }
 
-        result
+        let plain = ShortCodeGenerator::new_lowercase_alphanumeric(4);
+        assert!(!plain.has_valid_check_symbol(&['a', 'b', 'c', 'd']));
     }
 
-    /// Set the exhaustion strategy of this short code generator. Preserves
-    /// other state.
-    pub fn exhaustion_strategy(mut self, strategy: ExhaustionStrategy) -> Self {
-        self.exhaustion_strategy = strategy;
-        self
-    }
-}
+    #[test]
+    fn test_validate_full() {
+        let mut gen = ShortCodeGenerator::new_numeric(4)
+            .with_check_symbol()
+            .with_epoch(1);
+        let code = gen.next_string();
 
-/// Determines what happens when all codes (for a given alphabet and length) have
-/// been exhausted.
-#[derive(Clone, Copy, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub enum ExhaustionStrategy {
-    /// Repeat the sequences of short codes, starting with the first one.
-    /// This guarantees a collision if codes live indefinitely, but can be useful
-    /// when codes are used temporarily as it ensures that a code is never reused
-    /// until *all other possible codes* have been used between usages.
-    Cycle,
+        let result = gen.validate_full(&code, 3);
+        assert!(result.is_valid());
+        assert_eq!(
+            ValidationResult {
+                length_ok: true,
+                in_alphabet: true,
+                checksum_ok: Some(true),
+                partition: gen.partition_of(&code.chars().collect::<Vec<_>>()[1..5], 3),
+            },
+            result
+        );
 
-    /// Increase the length of the sequence, and continue. This is the default and
-    /// avoids collisions.
-    IncreaseLength,
+        let short = gen.validate_full(&code[..5], 3);
+        assert!(!short.length_ok && short.partition.is_none());
 
-    /// Panics. This is a fail-fast option
-    /// for cases where you don't expect the codes to ever become exhausted, and
-    /// either creating a collision or increasing the length of the code would be
-    /// incorrect behavior.
-    Panic,
-}
+        let letters = gen.validate_full("0abcd0", 3);
+        assert!(letters.length_ok && !letters.in_alphabet && letters.checksum_ok == Some(false));
 
-impl Default for ExhaustionStrategy {
-    fn default() -> Self {
-        ExhaustionStrategy::IncreaseLength
+        let mut typo: Vec<char> = code.chars().collect();
+        typo[2] = if typo[2] == '0' { '1' } else { '0' };
+        let typo: String = typo.into_iter().collect();
+        let result = gen.validate_full(&typo, 3);
+        assert_eq!(Some(false), result.checksum_ok);
+        assert!(!result.is_valid());
+
+        let plain = ShortCodeGenerator::new_numeric(4);
+        assert_eq!(None, plain.validate_full("1234", 3).checksum_ok);
+        assert!(plain.validate_full("1234", 3).is_valid());
     }
-}
 
-#[cfg(feature = "getrandom")]
-#[cfg(test)]
-mod tests {
-    use std::collections::HashSet;
+    #[test]
+    fn test_max_length_panic() {
+        let mut gen = ShortCodeGenerator::new_numeric(1).with_max_length(2);
 
-    use super::*;
+        for _ in 0..10 {
+            assert_eq!(1, gen.next_vec().len());
+        }
+        for _ in 0..100 {
+            assert_eq!(2, gen.next_vec().len());
+        }
+
+        let result = std::panic::catch_unwind(move || gen.next_vec());
+        assert!(result.is_err());
+    }
 
     #[test]
-    fn test_parallel_generators() {
-        let mut gen = ShortCodeGenerator::new_lowercase_alphanumeric(1);
-        let mut par_gens = gen.clone().into_partitioned_generators(7);
+    fn test_max_length_cycle() {
+        let mut gen = ShortCodeGenerator::new_numeric(1)
+            .with_max_length(2)
+            .max_length_fallback(ExhaustionStrategy::Cycle);
 
-        for _ in 0..10000 {
-            for par_gen in &mut par_gens {
-                let expected = gen.next_string();
-                let actual = par_gen.next_string();
-                assert_eq!(expected, actual);
-            }
+        for _ in 0..10 {
+            gen.next_vec();
         }
+        let first: Vec<Vec<char>> = (0..100).map(|_| gen.next_vec()).collect();
+        let second: Vec<Vec<char>> = (0..100).map(|_| gen.next_vec()).collect();
+        assert_eq!(first, second);
+        assert_eq!(2, gen.next_vec().len());
+        assert_eq!(1, gen.growth_count());
     }
 
     #[test]
@@ -331,7 +6614,7 @@ mod tests {
     }
 
     fn test_generator_helper(alphabet_size: u32, length: usize) {
-        let alphabet: Vec<u32> = (0..alphabet_size).into_iter().collect();
+        let alphabet: Vec<u32> = (0..alphabet_size).collect();
         let permutations: u64 = (alphabet_size as u64).pow(length as u32);
 
         let mut gen = ShortCodeGenerator::with_alphabet(alphabet, length)
@@ -370,6 +6653,32 @@ mod tests {
         test_generator_helper(7, 5);
     }
 
+    #[test]
+    fn test_offset_preserves_distinctness() {
+        for (alphabet_size, length) in [(2, 5), (3, 3), (10, 2), (12, 2), (36, 2)] {
+            let alphabet: Vec<u32> = (0..alphabet_size).collect();
+
+            for seed in 0..20 {
+                let rng = ChaCha12Rng::seed_from_u64(seed);
+                let mut gen =
+                    ShortCodeGenerator::with_alphabet_and_rng(alphabet.clone(), length, rng);
+                let capacity = gen.capacity();
+
+                // Seeded offsets, plus the extremes.
+                let offset = match seed {
+                    0 => 0,
+                    1 => capacity - 1,
+                    _ => gen.offset,
+                };
+                gen.offset = offset;
+
+                let seen: HashSet<u64> = (0..capacity).map(|_| gen.next_int()).collect();
+                assert_eq!(capacity, seen.len() as u64, "offset {}", offset);
+                assert!(seen.iter().all(|&value| value < capacity));
+            }
+        }
+    }
+
     #[test]
     fn test_lcm() {
         // Examples from wikipedia:
@@ -420,6 +6729,7 @@ mod tests {
     }
 
     #[test]
+    #[rustfmt::skip]
     fn test_0_1_3_stability() {
         let mut gen: ShortCodeGenerator<char> = serde_json::from_str(r#"
         {
@@ -458,6 +6768,7 @@ mod tests {
     }
 
     #[test]
+    #[rustfmt::skip]
     fn test_0_1_4_stability() {
         let mut gen: ShortCodeGenerator<char> = serde_json::from_str(r#"
         {
@@ -509,4 +6820,195 @@ mod tests {
 
         assert_eq!("jhigggg", gen.next_string());
     }
+
+    #[test]
+    fn test_serde_round_trip_across_growth() {
+        let mut gen = ShortCodeGenerator::new_numeric(2);
+        gen.next_n(150);
+        assert_eq!(3, gen.length);
+
+        let state = serde_json::to_value(&gen).unwrap();
+        assert_eq!(3, state["length"]);
+        assert_eq!(FORMAT_VERSION, state["format_version"]);
+
+        let mut restored: ShortCodeGenerator<char> = serde_json::from_value(state).unwrap();
+        for _ in 0..950 {
+            let code = gen.next_vec();
+            assert_eq!(3, code.len());
+            assert_eq!(code, restored.next_vec());
+        }
+        assert_eq!(4, restored.next_vec().len());
+    }
+
+    #[test]
+    fn test_format_version() {
+        let gen = ShortCodeGenerator::new_numeric(3);
+        let mut state = serde_json::to_value(&gen).unwrap();
+
+        // State from before versions were recorded.
+        state.as_object_mut().unwrap().remove("format_version");
+        let old: ShortCodeGenerator<char> = serde_json::from_value(state.clone()).unwrap();
+        assert_eq!(0, old.format_version);
+
+        // The version is updated when the length increases.
+        let mut old = old.exhaustion_strategy(ExhaustionStrategy::IncreaseLength);
+        old.next_n(1001);
+        assert_eq!(FORMAT_VERSION, old.format_version);
+
+        state["format_version"] = (FORMAT_VERSION + 1).into();
+        let err = serde_json::from_value::<ShortCodeGenerator<char>>(state).unwrap_err();
+        assert!(err.to_string().contains("Unsupported state format version"));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "word_pos")]
+    fn test_inconsistent_rng_word_pos() {
+        let mut state = serde_json::to_value(ShortCodeGenerator::new_numeric(3)).unwrap();
+        state["rng"]["word_pos"] = serde_json::json!(0);
+
+        // 2^70 words, past the 68-bit position the rng can represent.
+        let state = state
+            .to_string()
+            .replace(r#""word_pos":0"#, r#""word_pos":1180591620717411303424"#);
+        let _: ShortCodeGenerator<char> = serde_json::from_str(&state).unwrap();
+    }
+
+    #[test]
+    fn test_rng_round_trip() {
+        let mut gen = ShortCodeGenerator::new_numeric(1);
+        for _ in 0..25 {
+            gen.next_int();
+        }
+
+        let mut restored: ShortCodeGenerator<char> =
+            serde_json::from_str(&serde_json::to_string(&gen).unwrap()).unwrap();
+        for _ in 0..500 {
+            assert_eq!(gen.next_string(), restored.next_string());
+        }
+    }
+
+    #[test]
+    fn test_serde_round_trip_preserves_sequence() {
+        let strategies = [
+            ExhaustionStrategy::Cycle,
+            ExhaustionStrategy::IncreaseLength,
+            ExhaustionStrategy::IncreaseLengthPadded,
+        ];
+
+        for seed in 0..40 {
+            let mut rng = ChaCha12Rng::seed_from_u64(seed);
+            let alphabet: Vec<u32> = (0..rng.gen_range(2..12)).collect();
+            let length = rng.gen_range(1..4);
+            let strategy = strategies[rng.gen_range(0..strategies.len())];
+
+            let gen = ShortCodeGenerator::with_alphabet_and_rng(alphabet, length, rng.clone())
+                .exhaustion_strategy(strategy);
+            let mut gen = match rng.gen_range(0..3) {
+                0 => gen,
+                1 => gen.into_partitioned_generators(3).remove(1),
+                _ => gen.disjoint_partitions(2).remove(1),
+            };
+
+            for _ in 0..rng.gen_range(0..500) {
+                gen.next_int();
+            }
+
+            let mut restored: ShortCodeGenerator<u32> =
+                serde_json::from_str(&serde_json::to_string(&gen).unwrap()).unwrap();
+
+            for _ in 0..1000 {
+                assert_eq!(gen.next_vec(), restored.next_vec(), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_skip_before_next_alias() {
+        let gen = ShortCodeGenerator::new_numeric(3).into_partitioned_generators(2)[0].clone();
+        let mut expected = gen.clone();
+        expected.next_int();
+
+        let mut state = serde_json::to_value(&expected).unwrap();
+        let object = state.as_object_mut().unwrap();
+        let skip_before_next = object.remove("skip_before_next").unwrap();
+        object.insert("used".to_string(), skip_before_next);
+
+        let mut restored: ShortCodeGenerator<char> = serde_json::from_value(state).unwrap();
+        for _ in 0..100 {
+            assert_eq!(expected.next_string(), restored.next_string());
+        }
+    }
+
+    #[test]
+    fn test_has_generated() {
+        let round_trip = |gen: &ShortCodeGenerator<char>| -> ShortCodeGenerator<char> {
+            serde_json::from_str(&serde_json::to_string(gen).unwrap()).unwrap()
+        };
+
+        let mut gen = ShortCodeGenerator::new_numeric(3);
+        assert!(!gen.has_generated());
+        assert!(!round_trip(&gen).has_generated());
+
+        gen.next_vec();
+        assert!(gen.has_generated());
+        assert!(round_trip(&gen).has_generated());
+
+        gen.unget();
+        assert!(!gen.has_generated());
+
+        for mut partition in gen.into_partitioned_generators(3) {
+            assert!(!partition.has_generated());
+            assert!(!round_trip(&partition).has_generated());
+            partition.next_vec();
+            partition.next_vec();
+            partition.unget();
+            assert!(partition.has_generated());
+            assert!(round_trip(&partition).has_generated());
+        }
+    }
+
+    #[test]
+    fn test_unrecognized_exhaustion_strategy() {
+        let mut gen: ShortCodeGenerator<char> = serde_json::from_str(
+            r#"
+        {
+            "lcm": {
+                "first": 0,
+                "next": 0,
+                "m": 4,
+                "c": 1,
+                "a": 5,
+                "exhausted": false
+            },
+            "offset": 0,
+            "alphabet": ["a", "b"],
+            "length": 2,
+            "exhaustion_strategy": "SomeFutureStrategy"
+        }
+        "#,
+        )
+        .unwrap();
+
+        assert!(gen.exhaustion_strategy_unrecognized());
+        assert!(matches!(gen.current_strategy(), ExhaustionStrategy::Panic));
+
+        for _ in 0..4 {
+            gen.next_int();
+        }
+
+        // Falls back to panicking on exhaustion.
+        let result = std::panic::catch_unwind(move || gen.next_int());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_recognized_exhaustion_strategy() {
+        let gen = ShortCodeGenerator::new_numeric(2).exhaustion_strategy(ExhaustionStrategy::Cycle);
+        let json = serde_json::to_string(&gen).unwrap();
+        assert!(json.contains(r#""exhaustion_strategy":"Cycle""#));
+
+        let gen: ShortCodeGenerator<char> = serde_json::from_str(&json).unwrap();
+        assert!(!gen.exhaustion_strategy_unrecognized());
+    }
 }
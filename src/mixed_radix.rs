@@ -0,0 +1,297 @@
+use crate::lcm::{generate_a_mixed, LinearCongruentMultiplier};
+use crate::{AlphabetError, ExhaustionStrategy};
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+
+#[cfg(feature = "getrandom")]
+use rand_chacha::rand_core::SeedableRng;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Generates short codes where each position has its own alphabet, e.g. a
+/// letter followed by digits.
+///
+/// Like [`crate::ShortCodeGenerator`], every code in the space is generated
+/// exactly once per cycle. The space contains the product of the alphabet sizes.
+///
+/// ```
+/// let letters: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect();
+/// let digits: Vec<char> = "0123456789".chars().collect();
+///
+/// let mut generator = tiny_id::MixedRadixGenerator::with_alphabets(
+///     vec![letters, digits.clone(), digits],
+/// );
+/// let result: String = generator.next_string();
+/// assert_eq!(3, result.len());
+/// assert!(result.chars().next().unwrap().is_ascii_uppercase());
+/// ```
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct MixedRadixGenerator<T: Clone> {
+    lcm: LinearCongruentMultiplier,
+    offset: u64,
+
+    /// One alphabet per position, starting with the position that changes
+    /// fastest (the first symbol of the code).
+    alphabets: Vec<Vec<T>>,
+
+    exhaustion_strategy: ExhaustionStrategy,
+}
+
+impl MixedRadixGenerator<char> {
+    /// Consonants used by [`MixedRadixGenerator::new_pronounceable`]. Letters
+    /// with ambiguous pronunciation (c, q, w, x, y) are left out.
+    pub const CONSONANTS: &'static str = "bdfghjklmnprstvz";
+
+    /// Vowels used by [`MixedRadixGenerator::new_pronounceable`].
+    pub const VOWELS: &'static str = "aeiou";
+
+    /// Create a generator of pseudo-pronounceable codes like `"kabipo"`, made
+    /// of `syllables` consonant-vowel pairs.
+    ///
+    /// The code space contains `(16 * 5) ^ syllables` codes.
+    #[cfg(feature = "getrandom")]
+    pub fn new_pronounceable(syllables: usize) -> Self {
+        let consonants: Vec<char> = Self::CONSONANTS.chars().collect();
+        let vowels: Vec<char> = Self::VOWELS.chars().collect();
+
+        Self::with_alphabets(
+            (0..syllables)
+                .flat_map(|_| [consonants.clone(), vowels.clone()])
+                .collect(),
+        )
+    }
+
+    /// Return the next code, represented as a string.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_string(&mut self) -> String {
+        self.next_vec().into_iter().collect()
+    }
+}
+
+impl<T: Clone> MixedRadixGenerator<T> {
+    /// Create a mixed-radix generator using one alphabet per position, using
+    /// the given ChaCha12Rng random number generator.
+    ///
+    /// Panics if `alphabets` or any of its elements are empty, or if the
+    /// number of possible codes overflows a `u64`. See
+    /// [`MixedRadixGenerator::try_with_alphabets_and_rng`] for a non-panicking
+    /// version.
+    pub fn with_alphabets_and_rng(alphabets: Vec<Vec<T>>, rng: ChaCha12Rng) -> Self {
+        Self::try_with_alphabets_and_rng(alphabets, rng).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Create a mixed-radix generator using one alphabet per position, using
+    /// the given ChaCha12Rng random number generator.
+    ///
+    /// Returns an error if `alphabets` or any of its elements are empty, or
+    /// if the number of possible codes overflows a `u64`.
+    pub fn try_with_alphabets_and_rng(
+        alphabets: Vec<Vec<T>>,
+        mut rng: ChaCha12Rng,
+    ) -> Result<Self, AlphabetError> {
+        if alphabets.is_empty() || alphabets.iter().any(|alphabet| alphabet.is_empty()) {
+            return Err(AlphabetError::Empty);
+        }
+
+        let overflow = |alphabet_len| AlphabetError::Overflow {
+            alphabet_len,
+            length: alphabets.len(),
+        };
+        let radices = alphabets
+            .iter()
+            .map(|alphabet| u32::try_from(alphabet.len()).map_err(|_| overflow(alphabet.len())))
+            .collect::<Result<Vec<u32>, _>>()?;
+        let m = radices.iter().try_fold(1u64, |lhs, &rhs| {
+            lhs.checked_mul(rhs as u64)
+                .ok_or_else(|| overflow(rhs as usize))
+        })?;
+
+        let a = generate_a_mixed(&radices);
+        let lcm_seed = rng.gen_range(0..m);
+        let offset = rng.gen_range(0..m);
+
+        Ok(Self {
+            lcm: LinearCongruentMultiplier::new(lcm_seed, m, 1, a),
+            offset,
+            alphabets,
+            exhaustion_strategy: ExhaustionStrategy::Panic,
+        })
+    }
+
+    /// Create a mixed-radix generator using one alphabet per position.
+    #[cfg(feature = "getrandom")]
+    pub fn with_alphabets(alphabets: Vec<Vec<T>>) -> Self {
+        let mut seed: [u8; 32] = Default::default();
+        getrandom::getrandom(&mut seed).expect("Error getting entropy.");
+        let rng = ChaCha12Rng::from_seed(seed);
+        Self::with_alphabets_and_rng(alphabets, rng)
+    }
+
+    /// Set the exhaustion strategy of this generator. Preserves other state.
+    ///
+    /// The default is [`ExhaustionStrategy::Panic`]. Because there is no
+    /// alphabet for an additional position, [`ExhaustionStrategy::IncreaseLength`]
+    /// is not supported and behaves like `Panic`.
+    pub fn exhaustion_strategy(mut self, strategy: ExhaustionStrategy) -> Self {
+        self.exhaustion_strategy = strategy;
+        self
+    }
+
+    /// Return the next code, represented as an integer.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_int(&mut self) -> u64 {
+        if self.lcm.exhausted() {
+            match self.exhaustion_strategy {
+                ExhaustionStrategy::Cycle => {}
+                _ => panic!(
+                    "MixedRadixGenerator exhausted: bases={:?} capacity={}",
+                    self.alphabets.iter().map(Vec::len).collect::<Vec<_>>(),
+                    self.lcm.m
+                ),
+            }
+        }
+
+        crate::add_mod(self.lcm.next(), self.offset, self.lcm.m)
+    }
+
+    /// Return the next code, represented as a vector.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_vec(&mut self) -> Vec<T> {
+        let mut value = self.next_int();
+
+        self.alphabets
+            .iter()
+            .map(|alphabet| {
+                let radix = alphabet.len() as u64;
+                let symbol = alphabet[(value % radix) as usize].clone();
+                value /= radix;
+                symbol
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "getrandom")]
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn alphabets(sizes: &[u32]) -> Vec<Vec<u32>> {
+        sizes.iter().map(|&size| (0..size).collect()).collect()
+    }
+
+    fn assert_full_period(sizes: &[u32]) {
+        let capacity: u64 = sizes.iter().map(|&s| s as u64).product();
+        let mut gen = MixedRadixGenerator::with_alphabets(alphabets(sizes));
+        let mut seen = HashSet::new();
+
+        for _ in 0..capacity {
+            let next = gen.next_vec();
+            for (symbol, &size) in next.iter().zip(sizes) {
+                assert!(*symbol < size);
+            }
+            assert!(seen.insert(next));
+        }
+
+        assert_eq!(capacity, seen.len() as u64);
+    }
+
+    #[test]
+    fn test_full_period() {
+        assert_full_period(&[2, 3]);
+        assert_full_period(&[2, 2, 3]);
+        assert_full_period(&[26, 10, 10]);
+        assert_full_period(&[7, 1, 5]);
+        assert_full_period(&[36, 36]);
+    }
+
+    #[test]
+    fn test_per_position_alphabets() {
+        let letters: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect();
+        let digits: Vec<char> = "0123456789".chars().collect();
+        let mut gen = MixedRadixGenerator::with_alphabets(vec![letters, digits.clone(), digits]);
+
+        for _ in 0..2600 {
+            let code: Vec<char> = gen.next_string().chars().collect();
+            assert!(code[0].is_ascii_uppercase());
+            assert!(code[1].is_ascii_digit());
+            assert!(code[2].is_ascii_digit());
+        }
+    }
+
+    #[test]
+    fn test_pronounceable() {
+        let consonants = MixedRadixGenerator::CONSONANTS;
+        let vowels = MixedRadixGenerator::VOWELS;
+        let mut gen = MixedRadixGenerator::new_pronounceable(3);
+
+        let mut seen = HashSet::new();
+        for _ in 0..1000 {
+            let code = gen.next_string();
+            assert_eq!(6, code.len());
+
+            for (i, c) in code.chars().enumerate() {
+                if i % 2 == 0 {
+                    assert!(consonants.contains(c));
+                } else {
+                    assert!(vowels.contains(c));
+                }
+            }
+
+            assert!(seen.insert(code));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "MixedRadixGenerator exhausted: bases=[2, 3] capacity=6")]
+    fn test_exhaustion_panic() {
+        let mut gen = MixedRadixGenerator::with_alphabets(alphabets(&[2, 3]));
+
+        for _ in 0..7 {
+            gen.next_vec();
+        }
+    }
+
+    #[test]
+    fn test_exhaustion_cycle() {
+        let mut gen = MixedRadixGenerator::with_alphabets(alphabets(&[2, 3]))
+            .exhaustion_strategy(ExhaustionStrategy::Cycle);
+
+        let first: Vec<Vec<u32>> = (0..6).map(|_| gen.next_vec()).collect();
+        let second: Vec<Vec<u32>> = (0..6).map(|_| gen.next_vec()).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_try_with_alphabets_and_rng() {
+        let rng = || ChaCha12Rng::seed_from_u64(1);
+
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            MixedRadixGenerator::<u32>::try_with_alphabets_and_rng(vec![], rng()).err()
+        );
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            MixedRadixGenerator::try_with_alphabets_and_rng(alphabets(&[2, 0]), rng()).err()
+        );
+
+        // 16^15 fits in a u64, but 16^16 doesn't.
+        assert!(
+            MixedRadixGenerator::try_with_alphabets_and_rng(alphabets(&[16; 15]), rng()).is_ok()
+        );
+        assert_eq!(
+            Some(AlphabetError::Overflow {
+                alphabet_len: 16,
+                length: 16
+            }),
+            MixedRadixGenerator::try_with_alphabets_and_rng(alphabets(&[16; 16]), rng()).err()
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{checked_capacity, render, AlphabetError, ExhaustionStrategy};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Generates fixed-length codes in increasing order, e.g. for use as
+/// cursors that must sort in the order they were issued.
+///
+/// Codes are a plain counter rendered in base-N, most significant symbol
+/// first. If the alphabet is given in ascending order, each code is
+/// lexicographically greater than the one before it.
+///
+/// Unlike [`crate::ShortCodeGenerator`], codes are **not** scrambled: anyone
+/// who sees one code can predict the next. Only use this when sortability
+/// matters more than unguessability.
+///
+/// ```
+/// let mut generator = tiny_id::MonotonicGenerator::with_alphabet(
+///     "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect(),
+///     4,
+/// );
+/// let first = generator.next_string();
+/// let second = generator.next_string();
+/// assert_eq!("0000", first);
+/// assert!(first < second);
+/// ```
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct MonotonicGenerator<T: Clone> {
+    alphabet: Vec<T>,
+    length: u32,
+
+    /// The value of the next code to be generated.
+    next: u64,
+
+    exhaustion_strategy: ExhaustionStrategy,
+}
+
+impl MonotonicGenerator<char> {
+    /// Return the next code, represented as a string.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_string(&mut self) -> String {
+        self.next_vec().into_iter().collect()
+    }
+}
+
+impl<T: Clone> MonotonicGenerator<T> {
+    /// Create a monotonic generator using a given alphabet, starting from the
+    /// code made up entirely of the first symbol.
+    ///
+    /// Panics if the alphabet is empty or the number of possible codes
+    /// overflows a `u64`. See [`MonotonicGenerator::try_with_alphabet`] for a
+    /// non-panicking version.
+    pub fn with_alphabet(alphabet: Vec<T>, length: usize) -> Self {
+        Self::try_with_alphabet(alphabet, length).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Create a monotonic generator using a given alphabet, starting from the
+    /// code made up entirely of the first symbol.
+    ///
+    /// Returns an error if the alphabet is empty or the number of possible
+    /// codes overflows a `u64`.
+    pub fn try_with_alphabet(alphabet: Vec<T>, length: usize) -> Result<Self, AlphabetError> {
+        checked_capacity(alphabet.len(), length)?;
+
+        Ok(Self {
+            alphabet,
+            length: length as u32,
+            next: 0,
+            exhaustion_strategy: ExhaustionStrategy::Panic,
+        })
+    }
+
+    /// Set the exhaustion strategy of this generator. Preserves other state.
+    ///
+    /// The default is [`ExhaustionStrategy::Panic`]. A longer code would not
+    /// sort after the codes before it, so [`ExhaustionStrategy::IncreaseLength`]
+    /// is not supported and behaves like `Panic`. [`ExhaustionStrategy::Cycle`]
+    /// restarts from the first code, breaking the ordering at the wrap.
+    pub fn exhaustion_strategy(mut self, strategy: ExhaustionStrategy) -> Self {
+        self.exhaustion_strategy = strategy;
+        self
+    }
+
+    /// Return the next code, represented as an integer.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_int(&mut self) -> u64 {
+        let m = checked_capacity(self.alphabet.len(), self.length as usize)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        if self.next >= m {
+            match self.exhaustion_strategy {
+                ExhaustionStrategy::Cycle => self.next = 0,
+                _ => panic!(
+                    "MonotonicGenerator exhausted: base={} length={} capacity={}",
+                    self.alphabet.len(),
+                    self.length,
+                    m
+                ),
+            }
+        }
+
+        let value = self.next;
+        self.next += 1;
+        value
+    }
+
+    /// Return the next code, represented as a vector.
+    /// All `next_*` calls are equivalent to each other in terms of the
+    /// resulting state of self.
+    pub fn next_vec(&mut self) -> Vec<T> {
+        let value = self.next_int();
+        let mut result = render(value, &self.alphabet, self.length);
+
+        // `render` puts the least significant symbol first.
+        result.reverse();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increasing() {
+        let mut gen = MonotonicGenerator::with_alphabet("0123456789abcdef".chars().collect(), 3);
+
+        let mut last = gen.next_string();
+        assert_eq!("000", last);
+
+        for _ in 1..4096 {
+            let next = gen.next_string();
+            assert!(last < next);
+            last = next;
+        }
+
+        assert_eq!("fff", last);
+    }
+
+    #[test]
+    #[should_panic(expected = "MonotonicGenerator exhausted: base=2 length=2 capacity=4")]
+    fn test_exhaustion_panic() {
+        let mut gen = MonotonicGenerator::with_alphabet(vec![0u8, 1], 2);
+        for _ in 0..5 {
+            gen.next_vec();
+        }
+    }
+
+    #[test]
+    fn test_exhaustion_cycle() {
+        let mut gen = MonotonicGenerator::with_alphabet(vec![0u8, 1], 2)
+            .exhaustion_strategy(ExhaustionStrategy::Cycle);
+
+        let codes: Vec<Vec<u8>> = (0..5).map(|_| gen.next_vec()).collect();
+        assert_eq!(
+            vec![vec![0, 0], vec![0, 1], vec![1, 0], vec![1, 1], vec![0, 0]],
+            codes
+        );
+    }
+
+    #[test]
+    fn test_try_with_alphabet() {
+        assert_eq!(
+            Some(AlphabetError::Empty),
+            MonotonicGenerator::<u8>::try_with_alphabet(vec![], 3).err()
+        );
+
+        // 16^15 fits in a u64, but 16^16 doesn't.
+        let alphabet: Vec<u8> = (0..16).collect();
+        assert!(MonotonicGenerator::try_with_alphabet(alphabet.clone(), 15).is_ok());
+        assert_eq!(
+            Some(AlphabetError::Overflow {
+                alphabet_len: 16,
+                length: 16
+            }),
+            MonotonicGenerator::try_with_alphabet(alphabet, 16).err()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Signature symbols, derived from an HMAC-SHA256 of the rest of the code.
+//! Unlike a check symbol, a signature can't be computed without the key, so
+//! it detects deliberately forged codes as well as typos.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Compute HMAC-SHA256 (RFC 2104) of `message` under `key`.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length.");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+/// Return the largest number of base-`base` signature digits that the 256
+/// bits of an HMAC-SHA256 can fill.
+pub fn max_digits(base: u64) -> usize {
+    (256.0 / (base as f64).log2()) as usize
+}
+
+/// Return `count` base-`base` digits signing `digits` under `key`.
+///
+/// The message is each digit as a 4-byte big-endian integer. The
+/// HMAC-SHA256 of it, read as a big-endian integer, is written in base
+/// `base`, and its `count` least significant digits are the signature, least
+/// significant first.
+pub fn sign(key: &[u8], digits: &[u64], base: u64, count: usize) -> Vec<u64> {
+    let message: Vec<u8> = digits
+        .iter()
+        .flat_map(|&digit| (digit as u32).to_be_bytes())
+        .collect();
+    let mut mac = hmac_sha256(key, &message);
+
+    (0..count)
+        .map(|_| {
+            // Long division of the MAC by `base`, leaving the quotient in
+            // place.
+            let mut remainder = 0u64;
+            for byte in mac.iter_mut() {
+                let value = (remainder << 8) | *byte as u64;
+                *byte = (value / base) as u8;
+                remainder = value % base;
+            }
+            remainder
+        })
+        .collect()
+}
+
+/// Returns `true` if `signature` is the signature of `digits` under `key`.
+/// Compares every digit, so that the time taken doesn't reveal how many
+/// leading digits of a forged signature are correct.
+pub fn verify(key: &[u8], digits: &[u64], base: u64, signature: &[u64]) -> bool {
+    let expected = sign(key, digits, base, signature.len());
+    expected
+        .iter()
+        .zip(signature)
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // Test cases 1, 2 and 6 of RFC 4231.
+        assert_eq!(
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            hex(&hmac_sha256(&[0x0b; 20], b"Hi There"))
+        );
+        assert_eq!(
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?"))
+        );
+        assert_eq!(
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        let signature = sign(b"key", &[1, 2, 3], 36, 6);
+        assert_eq!(6, signature.len());
+        assert!(signature.iter().all(|&digit| digit < 36));
+        assert!(verify(b"key", &[1, 2, 3], 36, &signature));
+        assert!(!verify(b"other key", &[1, 2, 3], 36, &signature));
+        assert!(!verify(b"key", &[1, 2, 4], 36, &signature));
+
+        // Shorter signatures are a prefix of longer ones.
+        assert_eq!(signature[..3], sign(b"key", &[1, 2, 3], 36, 3)[..]);
+
+        assert_eq!(256, max_digits(2));
+        assert_eq!(49, max_digits(36));
+    }
+}
@@ -0,0 +1,119 @@
+//! Compact encoding of generator cursors, for
+//! [`crate::ShortCodeGenerator::cursor_token`].
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode `bytes` as unpadded base64url.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity((bytes.len() * 4).div_ceil(3));
+
+    for chunk in bytes.chunks(3) {
+        let mut buf = [0u8; 3];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            result.push(ALPHABET[index as usize] as char);
+        }
+    }
+
+    result
+}
+
+/// Decode unpadded base64url. Returns `None` if `s` isn't valid.
+pub fn decode(s: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len() * 3 / 4);
+
+    for chunk in s.as_bytes().chunks(4) {
+        if chunk.len() < 2 {
+            return None;
+        }
+
+        let mut bits = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let digit = ALPHABET.iter().position(|&a| a == c)? as u32;
+            bits |= digit << (18 - 6 * i);
+        }
+
+        let bytes = bits.to_be_bytes();
+        result.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+
+    Some(result)
+}
+
+/// 64-bit FNV-1a hasher. Unlike `DefaultHasher`, its output is stable
+/// between runs and platforms.
+pub struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf29ce484222325)
+    }
+}
+
+impl std::hash::Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    // Integers are hashed big-endian, and `usize` as a `u64`, so that the
+    // result doesn't depend on the platform.
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_be_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_be_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_be_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_be_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// Hash `values` with 64-bit FNV-1a, truncated to 32 bits.
+pub fn fingerprint(values: &[u64]) -> u32 {
+    use std::hash::Hasher;
+
+    let mut hasher = Fnv::default();
+    for value in values {
+        hasher.write_u64(*value);
+    }
+
+    hasher.finish() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for len in 0..10 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 200) as u8).collect();
+            assert_eq!(Some(bytes.clone()), decode(&encode(&bytes)));
+        }
+
+        assert_eq!("_-8", encode(&[0xff, 0xef]));
+        assert_eq!(None, decode("A"));
+        assert_eq!(None, decode("AB=="));
+    }
+}
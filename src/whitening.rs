@@ -0,0 +1,128 @@
+//! A fixed, nonlinear permutation of `[0, base ^ length)`, used to hide the
+//! linear structure of the underlying sequence in rendered codes. This is
+//! light obfuscation, not encryption: anyone who knows the permutation (it
+//! has no key) can invert it.
+//!
+//! Each round multiplies by a constant coprime to the modulus, adds a hash of
+//! the other digits to the least significant digit, and rotates that digit to
+//! the most significant position. Every step is a bijection, so the whole
+//! permutation is too.
+
+use crate::lcm::mod_inverse;
+
+/// Return the permuted value of `value`, which must be less than
+/// `base ^ length`.
+pub fn whiten(value: u64, base: u64, length: u32) -> u64 {
+    if length == 0 {
+        return value;
+    }
+
+    let Params { m, k, top, .. } = Params::new(base, length);
+    let mut value = value;
+
+    for _ in 0..length {
+        value = ((value as u128 * k as u128 + 1) % m as u128) as u64;
+        let (high, low) = (value / base, value % base);
+        let low = (low + mix(high) % base) % base;
+        value = low * top + high;
+    }
+
+    value
+}
+
+/// Invert [`whiten`].
+pub fn unwhiten(value: u64, base: u64, length: u32) -> u64 {
+    if length == 0 {
+        return value;
+    }
+
+    let Params {
+        m, k_inverse, top, ..
+    } = Params::new(base, length);
+    let mut value = value;
+
+    for _ in 0..length {
+        let (low, high) = (value / top, value % top);
+        let low = (low + base - mix(high) % base) % base;
+        value = high * base + low;
+        let m = m as u128;
+        value = ((value as u128 + m - 1) % m * k_inverse as u128 % m) as u64;
+    }
+
+    value
+}
+
+struct Params {
+    /// The modulus, `base ^ length`.
+    m: u64,
+
+    /// The multiplier, coprime to `m`.
+    k: u64,
+
+    k_inverse: u64,
+
+    /// The place value of the most significant digit.
+    top: u64,
+}
+
+impl Params {
+    fn new(base: u64, length: u32) -> Self {
+        let top = base.pow(length - 1);
+        let m = top * base;
+
+        // Start from the 64-bit golden ratio, and find the next value
+        // coprime to `m`.
+        let mut k = 0x9E37_79B9_7F4A_7C15 % m;
+        let k_inverse = loop {
+            if let Some(k_inverse) = mod_inverse(k, m) {
+                break k_inverse;
+            }
+            k = (k + 1) % m;
+        };
+
+        Params {
+            m,
+            k,
+            k_inverse,
+            top,
+        }
+    }
+}
+
+/// The SplitMix64 finalizer, a nonlinear mixing function.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whiten_is_permutation() {
+        for (base, length) in [(2u64, 1), (2, 10), (10, 3), (36, 2), (7, 4), (5, 0)] {
+            let m = base.pow(length);
+            let mut seen = vec![false; m as usize];
+
+            for value in 0..m {
+                let whitened = whiten(value, base, length);
+                assert!(whitened < m);
+                assert!(!seen[whitened as usize]);
+                seen[whitened as usize] = true;
+                assert_eq!(value, unwhiten(whitened, base, length));
+            }
+        }
+    }
+
+    #[test]
+    fn test_whiten_large() {
+        for (base, length) in [(62, 10), (2, 63), (3, 40)] {
+            let max = (base as u128).pow(length) - 1;
+            for value in [0, 1, 12345678901234, max as u64] {
+                assert_eq!(value, unwhiten(whiten(value, base, length), base, length));
+            }
+        }
+    }
+}